@@ -0,0 +1,59 @@
+use std::{fs, path::PathBuf};
+
+use advent_of_code_2021::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day2, day3, day4, day5, day6, day7,
+    day8, day9, input,
+    solution::Solution,
+    store::{Fixture, Store},
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Times parsing and each part of `S` on one input. Missing inputs are skipped so
+/// the suite still runs without the real puzzle inputs.
+fn bench_input<S: Solution>(c: &mut Criterion, kind: &str, path: PathBuf) {
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => {
+            eprintln!("Skipping day {} {}: no {}", S::DAY, kind, path.display());
+            return;
+        }
+    };
+    let parsed = S::parse(&text).expect("benchmark input parses");
+
+    let mut group = c.benchmark_group(format!("day{}/{}", S::DAY, kind));
+    group.sample_size(10);
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&text))));
+    group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&parsed))));
+    group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&parsed))));
+    group.finish();
+}
+
+fn bench_day<S: Solution>(c: &mut Criterion) {
+    bench_input::<S>(
+        c,
+        "example",
+        Store::local().path(&Fixture::named(S::DAY, "example")),
+    );
+    bench_input::<S>(c, "real", input::default_path(S::DAY));
+}
+
+criterion_group!(
+    days,
+    bench_day::<day1::Day1>,
+    bench_day::<day2::Day2>,
+    bench_day::<day3::Day3>,
+    bench_day::<day4::Day4>,
+    bench_day::<day5::Day5>,
+    bench_day::<day6::Day6>,
+    bench_day::<day7::Day7>,
+    bench_day::<day8::Day8>,
+    bench_day::<day9::Day9>,
+    bench_day::<day10::Day10>,
+    bench_day::<day11::Day11>,
+    bench_day::<day12::Day12>,
+    bench_day::<day13::Day13>,
+    bench_day::<day14::Day14>,
+    bench_day::<day15::Day15>,
+    bench_day::<day16::Day16>,
+);
+criterion_main!(days);
@@ -0,0 +1,12 @@
+#![no_main]
+
+use advent_of_code_2021::day10;
+use advent_of_code_2021_fuzz::check_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    check_day(10, input);
+    for line in input.lines() {
+        let _ = day10::parse_line(line);
+    }
+});
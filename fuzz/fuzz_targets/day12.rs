@@ -0,0 +1,12 @@
+#![no_main]
+
+use std::convert::TryFrom;
+
+use advent_of_code_2021::day12::CaveSystem;
+use advent_of_code_2021_fuzz::check_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    check_day(12, input);
+    let _ = CaveSystem::try_from(input);
+});
@@ -0,0 +1,6 @@
+#![no_main]
+
+use advent_of_code_2021_fuzz::check_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| check_day(14, input));
@@ -0,0 +1,13 @@
+#![no_main]
+
+use advent_of_code_2021::day16;
+use advent_of_code_2021_fuzz::check_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    check_day(16, input);
+    let _ = day16::hex_to_binary_string(input);
+    if let Ok(packet) = day16::parse_hex(input) {
+        let _ = day16::eval_packet(&packet);
+    }
+});
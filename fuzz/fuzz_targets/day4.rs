@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code_2021::day4;
+use advent_of_code_2021_fuzz::check_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    check_day(4, input);
+    let _ = day4::parse_all_boards(input);
+    let _ = day4::parse_draws(input);
+});
@@ -0,0 +1,15 @@
+//! Shared checks for the fuzz targets, one per day, run with
+//! `cargo fuzz run day<N>` from this directory.
+
+use advent_of_code_2021::registry;
+
+/// Parses `input` as the given day's puzzle input. Parsing may fail, but must
+/// not panic, and the error must point somewhere it can show.
+pub fn check_day(day: u8, input: &str) {
+    let day = registry::find(day).expect("fuzzed day is registered");
+
+    if let Err(err) = (day.check_input)(input) {
+        let _ = err.to_string();
+        let _ = err.excerpt(input);
+    }
+}
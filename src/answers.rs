@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use toml::{value::Table, Value};
+
+use crate::{
+    error::{Error, Result},
+    solution::Answer,
+};
+
+/// The expected answers for one day's fixtures, keyed by fixture name and part.
+/// Stored as TOML with a table per fixture:
+///
+/// ```toml
+/// [input]
+/// part1 = 1393
+/// part2 = 1359
+/// ```
+///
+/// Numbers become [`Answer::Number`], multi-line strings [`Answer::Grid`] (without
+/// the final newline) and other strings [`Answer::Text`].
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    answers: BTreeMap<(String, u8), Answer>,
+}
+
+fn part_number(key: &str) -> Option<u8> {
+    key.strip_prefix("part")?
+        .parse()
+        .ok()
+        .filter(|part| (1..=2).contains(part))
+}
+
+fn answer_from(value: &Value) -> Option<Answer> {
+    match value {
+        Value::Integer(n) => Some(Answer::Number(*n)),
+        Value::String(s) if s.contains('\n') => Some(Answer::Grid(s.trim_end().to_string())),
+        Value::String(s) => Some(Answer::Text(s.clone())),
+        _ => None,
+    }
+}
+
+fn value_from(answer: &Answer) -> Value {
+    match answer {
+        Answer::Number(n) => Value::Integer(*n),
+        Answer::Text(s) => Value::String(s.clone()),
+        Answer::Grid(grid) => Value::String(format!("{}\n", grid)),
+    }
+}
+
+fn table_error(reason: String) -> Error {
+    Error::parse("", "", reason)
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self> {
+        let value = text.parse::<Value>().map_err(|err| {
+            let (line, column) = err.line_col().unwrap_or((0, 0));
+            Error::Parse {
+                day: 0,
+                line: line + 1,
+                column: column + 1,
+                text: String::new(),
+                reason: err.to_string(),
+            }
+        })?;
+
+        let mut answers = Answers::default();
+        let fixtures = value
+            .as_table()
+            .ok_or_else(|| table_error("expected a table of fixtures".to_string()))?;
+
+        for (name, parts) in fixtures {
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| table_error(format!("expected a table for {}", name)))?;
+
+            for (part_key, value) in parts {
+                let part = part_number(part_key)
+                    .ok_or_else(|| table_error(format!("expected part1 or part2 in {}", name)))?;
+                let answer = answer_from(value).ok_or_else(|| {
+                    table_error(format!(
+                        "expected a number or string for {}.{}",
+                        name, part_key
+                    ))
+                })?;
+
+                answers.insert(name, part, answer);
+            }
+        }
+
+        Ok(answers)
+    }
+
+    /// The answers as TOML that [`Answers::parse`] reads back.
+    pub fn to_toml(&self) -> String {
+        let mut fixtures = Table::new();
+
+        for ((name, part), answer) in &self.answers {
+            let parts = fixtures
+                .entry(name.clone())
+                .or_insert_with(|| Value::Table(Table::new()));
+
+            if let Value::Table(parts) = parts {
+                parts.insert(format!("part{}", part), value_from(answer));
+            }
+        }
+
+        toml::to_string_pretty(&fixtures).unwrap_or_default()
+    }
+
+    pub fn get(&self, name: &str, part: u8) -> Option<&Answer> {
+        self.answers.get(&(name.to_string(), part))
+    }
+
+    pub fn insert(&mut self, name: &str, part: u8, answer: Answer) {
+        self.answers.insert((name.to_string(), part), answer);
+    }
+
+    /// Every known answer in name and part order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u8, &Answer)> {
+        self.answers
+            .iter()
+            .map(|((name, part), answer)| (name.as_str(), *part, answer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+[example]
+part1 = 7
+part2 = 5
+
+[input]
+part2 = """
+#.#
+.#.
+"""
+"#;
+
+    #[test]
+    fn parse_answers() {
+        let answers = Answers::parse(EXAMPLE).unwrap();
+
+        assert_eq!(answers.get("example", 1), Some(&Answer::Number(7)));
+        assert_eq!(answers.get("example", 2), Some(&Answer::Number(5)));
+        assert_eq!(
+            answers.get("input", 2),
+            Some(&Answer::Grid("#.#\n.#.".to_string()))
+        );
+        assert_eq!(answers.get("input", 1), None);
+        assert_eq!(answers.iter().count(), 3);
+    }
+
+    #[test]
+    fn to_toml_test() {
+        let mut answers = Answers::parse(EXAMPLE).unwrap();
+        answers.insert("other", 1, Answer::from("abc"));
+
+        assert_eq!(Answers::parse(&answers.to_toml()).unwrap(), answers);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Answers::parse("[input]\npart1 = ").is_err());
+        assert!(Answers::parse("input = 1").is_err());
+        assert!(Answers::parse("[input]\npart3 = 1").is_err());
+        assert!(Answers::parse("[input]\npart1 = 1.5").is_err());
+    }
+}
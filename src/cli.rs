@@ -0,0 +1,791 @@
+use advent_of_code_2021::{day1, render};
+
+pub const USAGE: &str =
+    "Usage: aoc run <day|all> [--part 1|2] [--input PATH|- | --fixture NAME] [--json] [--jobs N]
+       aoc fixtures list [day]
+       aoc fixtures add <day> <name> [--input PATH|-] [--part1 ANSWER] [--part2 ANSWER]
+       aoc fixtures validate [day]
+       aoc gen <day> [--size N] [--seed S]
+       aoc submit <day> <part> [--input PATH|-] [--answer ANSWER] [--url URL]
+       aoc render <day> --out FILE.png|FILE.gif [--input PATH|-] [--scale N] [--frames]
+       aoc watch <day> [--input PATH|-]
+       aoc new <day>
+       aoc sonar [--input PATH|-] [--window N]... [--csv FILE|-]
+       aoc course --out FILE.csv|FILE.svg [--input PATH|-] [--part 1|2] [--lenient]
+
+Inputs default to $AOC_INPUT_DIR/dayN.txt, or the real input in the crate's
+fixture store under input/. An input of - reads from stdin. --fixture runs a
+stored fixture such as 'example'. --json prints answers and timings as JSON.
+--jobs runs up to N days and parts at once; output stays in day order.
+gen prints a random input; the same size and seed always give the same one.
+submit sends a part's answer, or the one given, to $AOC_URL or --url (default
+https://adventofcode.com) with the session cookie in $AOC_SESSION. Every answer
+sent is logged next to the day's fixtures, and answers the log shows are wrong
+are not sent again.
+render draws days 9, 11, 13 and 15 with N pixels per cell. A GIF animates every
+step; a PNG shows the last one, or is numbered FILE-N.png per step with --frames.
+watch steps through days 6, 11, 13 and 14 in the terminal: space pauses and
+plays, s steps, + and - change the speed and q quits.
+new writes src/dayN.rs from a template with an empty example fixture, and adds
+it to src/lib.rs and the registry.
+sonar reports on a day 1 depth series: its longest increasing run, largest
+jump and how the sums of windows of N depths change (by default 1 and 3). --csv
+writes every window's sum to FILE, or to stdout instead of the report.
+course records every state of the day 2 submarine, moving as in part 2 unless
+--part 1 is given, as CSV or as an SVG depth profile. --lenient skips commands
+that don't parse, listing them, instead of stopping at the first.";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Selection {
+    All,
+    Day(u8),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run {
+        selection: Selection,
+        part: Option<u8>,
+        input: Option<String>,
+        fixture: Option<String>,
+        json: bool,
+        jobs: usize,
+    },
+    ListFixtures {
+        day: Option<u8>,
+    },
+    AddFixture {
+        day: u8,
+        name: String,
+        input: Option<String>,
+        answers: Vec<(u8, String)>,
+    },
+    ValidateFixtures {
+        day: Option<u8>,
+    },
+    Generate {
+        day: u8,
+        size: usize,
+        seed: u64,
+    },
+    Submit {
+        day: u8,
+        part: u8,
+        input: Option<String>,
+        answer: Option<String>,
+        url: Option<String>,
+    },
+    Render {
+        day: u8,
+        out: String,
+        input: Option<String>,
+        scale: usize,
+        frames: bool,
+    },
+    Watch {
+        day: u8,
+        input: Option<String>,
+    },
+    New {
+        day: u8,
+    },
+    Sonar {
+        input: Option<String>,
+        windows: Vec<usize>,
+        csv: Option<String>,
+    },
+    Course {
+        out: String,
+        input: Option<String>,
+        part: u8,
+        lenient: bool,
+    },
+}
+
+pub const DEFAULT_SIZE: usize = 100;
+
+fn parse_selection(s: &str) -> Result<Selection, String> {
+    if s == "all" {
+        return Ok(Selection::All);
+    }
+
+    s.parse::<u8>()
+        .map(Selection::Day)
+        .map_err(|_| format!("'{}' is not a day number or 'all'", s))
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    s.parse::<u8>()
+        .map_err(|_| format!("'{}' is not a day number", s))
+}
+
+fn parse_part(s: &str) -> Result<u8, String> {
+    match s {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("'{}' is not a valid part, expected 1 or 2", s)),
+    }
+}
+
+fn parse_run<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = None;
+    let mut part = None;
+    let mut input = None;
+    let mut fixture = None;
+    let mut json = false;
+    let mut jobs = 1;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                let value = args.next().ok_or("--part needs a value")?;
+                part = Some(parse_part(&value)?);
+            }
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            "--fixture" | "-f" => {
+                fixture = Some(args.next().ok_or("--fixture needs a value")?);
+            }
+            "--json" => json = true,
+            "--jobs" | "-j" => {
+                jobs = parse_number(&arg, args.next())?;
+                if jobs == 0 {
+                    return Err("--jobs needs at least 1".to_string());
+                }
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ if selection.is_none() => selection = Some(parse_selection(&arg)?),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    let selection = selection.ok_or("Missing day")?;
+    if selection == Selection::All && (input.is_some() || fixture.is_some()) {
+        return Err("--input and --fixture can only be used with a single day".to_string());
+    }
+    if input.is_some() && fixture.is_some() {
+        return Err("Use either --input or --fixture".to_string());
+    }
+
+    Ok(Command::Run {
+        selection,
+        part,
+        input,
+        fixture,
+        json,
+        jobs,
+    })
+}
+
+fn parse_optional_day<I>(mut args: I) -> Result<Option<u8>, String>
+where
+    I: Iterator<Item = String>,
+{
+    let day = args.next().map(|day| parse_day(&day)).transpose()?;
+
+    match args.next() {
+        Some(arg) => Err(format!("Unexpected argument '{}'", arg)),
+        None => Ok(day),
+    }
+}
+
+fn parse_add<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut positional = Vec::new();
+    let mut input = None;
+    let mut answers = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            "--part1" | "--part2" => {
+                let value = args.next().ok_or(format!("{} needs a value", arg))?;
+                answers.push((parse_part(&arg["--part".len()..])?, value));
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    match positional.as_slice() {
+        [day, name] => Ok(Command::AddFixture {
+            day: parse_day(day)?,
+            name: name.clone(),
+            input,
+            answers,
+        }),
+        [] | [_] => Err("Missing day or fixture name".to_string()),
+        [_, _, extra, ..] => Err(format!("Unexpected argument '{}'", extra)),
+    }
+}
+
+fn parse_fixtures<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    match args.next().as_deref() {
+        Some("list") => Ok(Command::ListFixtures {
+            day: parse_optional_day(args)?,
+        }),
+        Some("add") => parse_add(args),
+        Some("validate") => Ok(Command::ValidateFixtures {
+            day: parse_optional_day(args)?,
+        }),
+        Some(command) => Err(format!("Unknown fixtures command '{}'", command)),
+        None => Err("Missing fixtures command".to_string()),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or(format!("{} needs a value", option))?;
+    value
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid {} value", value, option))
+}
+
+fn parse_gen<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut size = DEFAULT_SIZE;
+    let mut seed = 0;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" | "-n" => size = parse_number(&arg, args.next())?,
+            "--seed" | "-s" => seed = parse_number(&arg, args.next())?,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Generate {
+        day: day.ok_or("Missing day")?,
+        size,
+        seed,
+    })
+}
+
+fn parse_submit<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut positional = Vec::new();
+    let mut input = None;
+    let mut answer = None;
+    let mut url = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            "--answer" | "-a" => {
+                answer = Some(args.next().ok_or("--answer needs a value")?);
+            }
+            "--url" => {
+                url = Some(args.next().ok_or("--url needs a value")?);
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    if input.is_some() && answer.is_some() {
+        return Err("Use either --input or --answer".to_string());
+    }
+
+    match positional.as_slice() {
+        [day, part] => Ok(Command::Submit {
+            day: parse_day(day)?,
+            part: parse_part(part)?,
+            input,
+            answer,
+            url,
+        }),
+        [] | [_] => Err("Missing day or part".to_string()),
+        [_, _, extra, ..] => Err(format!("Unexpected argument '{}'", extra)),
+    }
+}
+
+fn parse_render<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut out = None;
+    let mut input = None;
+    let mut scale = render::DEFAULT_SCALE;
+    let mut frames = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" | "-o" => {
+                out = Some(args.next().ok_or("--out needs a value")?);
+            }
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            "--scale" => {
+                scale = parse_number(&arg, args.next())?;
+                if scale == 0 {
+                    return Err("--scale needs at least 1".to_string());
+                }
+            }
+            "--frames" => frames = true,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    let out = out.ok_or("Missing --out")?;
+    if !out.ends_with(".png") && !out.ends_with(".gif") {
+        return Err(format!("'{}' should end in .png or .gif", out));
+    }
+    if frames && !out.ends_with(".png") {
+        return Err("--frames only writes PNGs".to_string());
+    }
+
+    Ok(Command::Render {
+        day: day.ok_or("Missing day")?,
+        out,
+        input,
+        scale,
+        frames,
+    })
+}
+
+fn parse_watch<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Watch {
+        day: day.ok_or("Missing day")?,
+        input,
+    })
+}
+
+fn parse_sonar<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut input = None;
+    let mut windows = Vec::new();
+    let mut csv = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            "--window" | "-w" => {
+                let size = parse_number(&arg, args.next())?;
+                if size == 0 {
+                    return Err("--window needs at least 1".to_string());
+                }
+                windows.push(size);
+            }
+            "--csv" => {
+                csv = Some(args.next().ok_or("--csv needs a value")?);
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    if windows.is_empty() {
+        windows = day1::REPORT_WINDOWS.to_vec();
+    }
+
+    Ok(Command::Sonar {
+        input,
+        windows,
+        csv,
+    })
+}
+
+fn parse_course<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut out = None;
+    let mut input = None;
+    let mut part = 2;
+    let mut lenient = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" | "-o" => {
+                out = Some(args.next().ok_or("--out needs a value")?);
+            }
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            "--part" | "-p" => {
+                let value = args.next().ok_or("--part needs a value")?;
+                part = parse_part(&value)?;
+            }
+            "--lenient" => lenient = true,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    let out = out.ok_or("Missing --out")?;
+    if !out.ends_with(".csv") && !out.ends_with(".svg") {
+        return Err(format!("'{}' should end in .csv or .svg", out));
+    }
+
+    Ok(Command::Course {
+        out,
+        input,
+        part,
+        lenient,
+    })
+}
+
+/// Parses the arguments after the program name.
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("run") => parse_run(args),
+        Some("fixtures") => parse_fixtures(args),
+        Some("gen") => parse_gen(args),
+        Some("submit") => parse_submit(args),
+        Some("render") => parse_render(args),
+        Some("watch") => parse_watch(args),
+        Some("new") => match parse_optional_day(args)? {
+            Some(day) => Ok(Command::New { day }),
+            None => Err("Missing day".to_string()),
+        },
+        Some("sonar") => parse_sonar(args),
+        Some("course") => parse_course(args),
+        Some(command) => Err(format!("Unknown command '{}'", command)),
+        None => Err("Missing command".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn run_day() {
+        assert_eq!(
+            parse_args(args("run 5")),
+            Ok(Command::Run {
+                selection: Selection::Day(5),
+                part: None,
+                input: None,
+                fixture: None,
+                json: false,
+                jobs: 1
+            })
+        );
+
+        assert_eq!(
+            parse_args(args("run 14 --part 2 --input other.txt")),
+            Ok(Command::Run {
+                selection: Selection::Day(14),
+                part: Some(2),
+                input: Some("other.txt".to_string()),
+                fixture: None,
+                json: false,
+                jobs: 1
+            })
+        );
+    }
+
+    #[test]
+    fn run_all() {
+        assert_eq!(
+            parse_args(args("run all -p 1")),
+            Ok(Command::Run {
+                selection: Selection::All,
+                part: Some(1),
+                input: None,
+                fixture: None,
+                json: false,
+                jobs: 1
+            })
+        );
+
+        assert!(parse_args(args("run all --input day1.txt")).is_err());
+
+        assert_eq!(
+            parse_args(args("run all -j 4 --part 2")),
+            Ok(Command::Run {
+                selection: Selection::All,
+                part: Some(2),
+                input: None,
+                fixture: None,
+                json: false,
+                jobs: 4
+            })
+        );
+        assert!(parse_args(args("run all --jobs 0")).is_err());
+        assert!(parse_args(args("run all --jobs many")).is_err());
+
+        assert_eq!(
+            parse_args(args("run all --json")),
+            Ok(Command::Run {
+                selection: Selection::All,
+                part: None,
+                input: None,
+                fixture: None,
+                json: true,
+                jobs: 1
+            })
+        );
+    }
+
+    #[test]
+    fn fixtures() {
+        assert_eq!(
+            parse_args(args("run 9 --fixture example")),
+            Ok(Command::Run {
+                selection: Selection::Day(9),
+                part: None,
+                input: None,
+                fixture: Some("example".to_string()),
+                json: false,
+                jobs: 1
+            })
+        );
+        assert_eq!(
+            parse_args(args("fixtures list")),
+            Ok(Command::ListFixtures { day: None })
+        );
+        assert_eq!(
+            parse_args(args("fixtures validate 3")),
+            Ok(Command::ValidateFixtures { day: Some(3) })
+        );
+        assert_eq!(
+            parse_args(args("fixtures add 1 short --input - --part2 5")),
+            Ok(Command::AddFixture {
+                day: 1,
+                name: "short".to_string(),
+                input: Some("-".to_string()),
+                answers: vec![(2, "5".to_string())],
+            })
+        );
+
+        assert!(parse_args(args("run all --fixture example")).is_err());
+        assert!(parse_args(args("run 1 --fixture example --input a.txt")).is_err());
+        assert!(parse_args(args("fixtures add 1")).is_err());
+        assert!(parse_args(args("fixtures list 1 2")).is_err());
+        assert!(parse_args(args("fixtures remove 1")).is_err());
+    }
+
+    #[test]
+    fn gen() {
+        assert_eq!(
+            parse_args(args("gen 15 --size 1000 --seed 42")),
+            Ok(Command::Generate {
+                day: 15,
+                size: 1000,
+                seed: 42
+            })
+        );
+        assert_eq!(
+            parse_args(args("gen 5")),
+            Ok(Command::Generate {
+                day: 5,
+                size: DEFAULT_SIZE,
+                seed: 0
+            })
+        );
+
+        assert!(parse_args(args("gen")).is_err());
+        assert!(parse_args(args("gen 5 --size")).is_err());
+        assert!(parse_args(args("gen 5 --seed -1")).is_err());
+        assert!(parse_args(args("gen 5 6")).is_err());
+    }
+
+    #[test]
+    fn submit() {
+        assert_eq!(
+            parse_args(args("submit 1 2 --url http://localhost:8080")),
+            Ok(Command::Submit {
+                day: 1,
+                part: 2,
+                input: None,
+                answer: None,
+                url: Some("http://localhost:8080".to_string())
+            })
+        );
+        assert_eq!(
+            parse_args(args("submit 13 2 --answer ABCDEFGH")),
+            Ok(Command::Submit {
+                day: 13,
+                part: 2,
+                input: None,
+                answer: Some("ABCDEFGH".to_string()),
+                url: None
+            })
+        );
+
+        assert!(parse_args(args("submit 1")).is_err());
+        assert!(parse_args(args("submit 1 3")).is_err());
+        assert!(parse_args(args("submit 1 1 --answer")).is_err());
+        assert!(parse_args(args("submit 1 1 --input in.txt --answer 5")).is_err());
+    }
+
+    #[test]
+    fn render() {
+        assert_eq!(
+            parse_args(args("render 11 --out octopuses.gif --scale 8")),
+            Ok(Command::Render {
+                day: 11,
+                out: "octopuses.gif".to_string(),
+                input: None,
+                scale: 8,
+                frames: false
+            })
+        );
+        assert_eq!(
+            parse_args(args("render 13 -o paper.png --frames -i -")),
+            Ok(Command::Render {
+                day: 13,
+                out: "paper.png".to_string(),
+                input: Some("-".to_string()),
+                scale: render::DEFAULT_SCALE,
+                frames: true
+            })
+        );
+
+        assert!(parse_args(args("render 9")).is_err());
+        assert!(parse_args(args("render --out basins.png")).is_err());
+        assert!(parse_args(args("render 9 --out basins.jpg")).is_err());
+        assert!(parse_args(args("render 9 --out basins.gif --frames")).is_err());
+        assert!(parse_args(args("render 9 --out basins.png --scale 0")).is_err());
+    }
+
+    #[test]
+    fn watch() {
+        assert_eq!(
+            parse_args(args("watch 11")),
+            Ok(Command::Watch {
+                day: 11,
+                input: None
+            })
+        );
+        assert_eq!(
+            parse_args(args("watch 6 --input fish.txt")),
+            Ok(Command::Watch {
+                day: 6,
+                input: Some("fish.txt".to_string())
+            })
+        );
+
+        assert!(parse_args(args("watch")).is_err());
+        assert!(parse_args(args("watch 6 7")).is_err());
+        assert!(parse_args(args("watch 6 --speed 2")).is_err());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(parse_args(args("new 17")), Ok(Command::New { day: 17 }));
+
+        assert!(parse_args(args("new")).is_err());
+        assert!(parse_args(args("new 17 18")).is_err());
+        assert!(parse_args(args("new seventeen")).is_err());
+    }
+
+    #[test]
+    fn sonar() {
+        assert_eq!(
+            parse_args(args("sonar")),
+            Ok(Command::Sonar {
+                input: None,
+                windows: day1::REPORT_WINDOWS.to_vec(),
+                csv: None
+            })
+        );
+        assert_eq!(
+            parse_args(args("sonar -i depths.txt --window 5 -w 1 --csv -")),
+            Ok(Command::Sonar {
+                input: Some("depths.txt".to_string()),
+                windows: vec![5, 1],
+                csv: Some("-".to_string())
+            })
+        );
+
+        assert!(parse_args(args("sonar --window 0")).is_err());
+        assert!(parse_args(args("sonar --window")).is_err());
+        assert!(parse_args(args("sonar --csv")).is_err());
+        assert!(parse_args(args("sonar 1")).is_err());
+    }
+
+    #[test]
+    fn course() {
+        assert_eq!(
+            parse_args(args("course --out course.svg")),
+            Ok(Command::Course {
+                out: "course.svg".to_string(),
+                input: None,
+                part: 2,
+                lenient: false
+            })
+        );
+        assert_eq!(
+            parse_args(args("course -o course.csv -p 1 -i - --lenient")),
+            Ok(Command::Course {
+                out: "course.csv".to_string(),
+                input: Some("-".to_string()),
+                part: 1,
+                lenient: true
+            })
+        );
+
+        assert!(parse_args(args("course")).is_err());
+        assert!(parse_args(args("course --out course.png")).is_err());
+        assert!(parse_args(args("course --out course.svg --part 3")).is_err());
+        assert!(parse_args(args("course 2 --out course.svg")).is_err());
+    }
+
+    #[test]
+    fn bad_args() {
+        assert!(parse_args(args("")).is_err());
+        assert!(parse_args(args("walk 1")).is_err());
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("run one")).is_err());
+        assert!(parse_args(args("run 1 --part 3")).is_err());
+        assert!(parse_args(args("run 1 --part")).is_err());
+        assert!(parse_args(args("run 1 2")).is_err());
+        assert!(parse_args(args("run 1 --verbose")).is_err());
+    }
+}
@@ -1,51 +1,618 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use std::{collections::VecDeque, fmt, io::Write};
+
+use crate::{
+    error::{parse_token, Error, Result},
+    solution::{Answer, Solution, Streaming},
 };
 
-pub fn part1() {
-    let mut input = BufReader::new(File::open("input/day1.txt").expect("day 1 input missing"));
+/// Counts increases between the sums of consecutive windows of `size` values.
+/// Neighbouring windows share all but one value, so a sum goes up exactly when
+/// the value entering the window beats the one leaving it.
+#[derive(Debug, Clone)]
+pub struct WindowIncreases<T> {
+    size: usize,
+    window: VecDeque<T>,
+    increases: usize,
+}
+
+impl<T: PartialOrd> WindowIncreases<T> {
+    /// Panics if `size` is 0.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "windows need at least one value");
+
+        WindowIncreases {
+            size,
+            window: VecDeque::with_capacity(size),
+            increases: 0,
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        if self.window.len() == self.size {
+            if let Some(leaving) = self.window.pop_front() {
+                if value > leaving {
+                    self.increases += 1;
+                }
+            }
+        }
+
+        self.window.push_back(value);
+    }
+
+    /// `None` until there has been a whole window.
+    pub fn increases(&self) -> Option<usize> {
+        (self.window.len() == self.size).then_some(self.increases)
+    }
+}
+
+/// `None` when `values` is shorter than one window. Panics if `size` is 0.
+pub fn window_increases<I>(values: I, size: usize) -> Option<usize>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    let mut windows = WindowIncreases::new(size);
+    values.into_iter().for_each(|value| windows.add(value));
+
+    windows.increases()
+}
+
+/// What streaming keeps of the depths: the windows for both parts.
+#[derive(Debug)]
+pub struct DepthSummary {
+    single: WindowIncreases<i32>,
+    triple: WindowIncreases<i32>,
+}
+
+impl Default for DepthSummary {
+    fn default() -> Self {
+        DepthSummary {
+            single: WindowIncreases::new(1),
+            triple: WindowIncreases::new(3),
+        }
+    }
+}
+
+impl DepthSummary {
+    pub fn add(&mut self, depth: i32) {
+        self.single.add(depth);
+        self.triple.add(depth);
+    }
+}
+
+fn part1_answer(increases: Option<usize>) -> Result<Answer> {
+    increases
+        .map(Answer::from)
+        .ok_or_else(|| Error::unsolvable("no depths in input"))
+}
+
+fn part2_answer(increases: Option<usize>) -> Result<Answer> {
+    increases
+        .map(Answer::from)
+        .ok_or_else(|| Error::unsolvable("fewer than 3 depths in input"))
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .map(|line| parse_token(input, line.trim(), "a depth"))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        part1_answer(window_increases(input, 1))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        part2_answer(window_increases(input, 3))
+    }
+}
+
+impl Streaming for Day1 {
+    type Summary = DepthSummary;
+
+    fn add_line(summary: &mut Self::Summary, line: &str) -> Result<()> {
+        summary.add(parse_token(line, line.trim(), "a depth")?);
+        Ok(())
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Result<Answer> {
+        part1_answer(summary.single.increases())
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Result<Answer> {
+        part2_answer(summary.triple.increases())
+    }
+}
+
+/// The window sizes a sonar report summarises unless told otherwise.
+pub const REPORT_WINDOWS: [usize; 2] = [1, 3];
+
+/// Panics if `size` is 0.
+pub fn window_sums(depths: &[i32], size: usize) -> Vec<i64> {
+    assert!(size > 0, "windows need at least one value");
+    if depths.len() < size {
+        return Vec::new();
+    }
+
+    let mut sum = depths[..size].iter().map(|&depth| depth as i64).sum();
+    let mut sums = vec![sum];
+    for (&leaving, &entering) in depths.iter().zip(&depths[size..]) {
+        sum += entering as i64 - leaving as i64;
+        sums.push(sum);
+    }
+
+    sums
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Run {
+    pub start: usize,
+    pub len: usize,
+    pub first: i32,
+    pub last: i32,
+}
+
+/// The first of the longest runs of strictly increasing depths.
+pub fn longest_increasing_run(depths: &[i32]) -> Option<Run> {
+    let mut longest: Option<Run> = None;
+    let mut start = 0;
+
+    for end in 1..=depths.len() {
+        if end == depths.len() || depths[end] <= depths[end - 1] {
+            let run = Run {
+                start,
+                len: end - start,
+                first: depths[start],
+                last: depths[end - 1],
+            };
+            if longest.is_none_or(|longest| run.len > longest.len) {
+                longest = Some(run);
+            }
+            start = end;
+        }
+    }
+
+    longest
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Jump {
+    pub at: usize,
+    pub from: i32,
+    pub to: i32,
+}
+
+impl Jump {
+    pub fn change(&self) -> i64 {
+        self.to as i64 - self.from as i64
+    }
+}
+
+/// The first of the largest changes between neighbouring depths, up or down.
+pub fn largest_jump(depths: &[i32]) -> Option<Jump> {
+    let mut largest: Option<Jump> = None;
 
-    let mut first_line = String::new();
-    input
-        .read_line(&mut first_line)
-        .expect("Error reading first line");
+    for (i, pair) in depths.windows(2).enumerate() {
+        let jump = Jump {
+            at: i + 1,
+            from: pair[0],
+            to: pair[1],
+        };
+        if largest.is_none_or(|largest| jump.change().abs() > largest.change().abs()) {
+            largest = Some(jump);
+        }
+    }
+
+    largest
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Window {
+    pub start: usize,
+    pub sum: i64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindowSummary {
+    pub size: usize,
+    pub windows: usize,
+    pub increases: usize,
+    pub decreases: usize,
+    pub unchanged: usize,
+    pub smallest: Option<Window>,
+    pub largest: Option<Window>,
+}
+
+impl WindowSummary {
+    /// Panics if `size` is 0.
+    pub fn new(depths: &[i32], size: usize) -> Self {
+        let sums = window_sums(depths, size);
+        let changes = || sums.windows(2).map(|pair| pair[1].cmp(&pair[0]));
+        let windows = sums
+            .iter()
+            .enumerate()
+            .map(|(start, &sum)| Window { start, sum });
+
+        WindowSummary {
+            size,
+            windows: sums.len(),
+            increases: changes().filter(|change| change.is_gt()).count(),
+            decreases: changes().filter(|change| change.is_lt()).count(),
+            unchanged: changes().filter(|change| change.is_eq()).count(),
+            smallest: windows.clone().min_by_key(|window| window.sum),
+            // max_by_key keeps the last of equal sums, so look from the end
+            largest: windows.rev().max_by_key(|window| window.sum),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SonarReport {
+    pub depths: usize,
+    pub shallowest: i32,
+    pub deepest: i32,
+    pub longest_run: Run,
+    pub largest_jump: Option<Jump>,
+    pub windows: Vec<WindowSummary>,
+}
+
+impl SonarReport {
+    /// Panics if any of `sizes` is 0.
+    pub fn new(depths: &[i32], sizes: &[usize]) -> Result<Self> {
+        let (shallowest, deepest, longest_run) = match (
+            depths.iter().min(),
+            depths.iter().max(),
+            longest_increasing_run(depths),
+        ) {
+            (Some(&shallowest), Some(&deepest), Some(run)) => (shallowest, deepest, run),
+            _ => return Err(Error::unsolvable("no depths in input")),
+        };
+
+        Ok(SonarReport {
+            depths: depths.len(),
+            shallowest,
+            deepest,
+            longest_run,
+            largest_jump: largest_jump(depths),
+            windows: sizes
+                .iter()
+                .map(|&size| WindowSummary::new(depths, size))
+                .collect(),
+        })
+    }
+}
 
-    println!("First line: {}", first_line);
-    let first_num: i32 = first_line.trim().parse().expect("First line not a number");
-    let init = (0, first_num);
+fn lines(start: usize, len: usize) -> String {
+    match len {
+        1 => format!("line {}", start + 1),
+        _ => format!("lines {}-{}", start + 1, start + len),
+    }
+}
 
-    let (total, _) = input.lines().fold(init, |(total, prev_num), line| {
-        let line = line.expect("Not a valid line?");
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
+
+impl fmt::Display for SonarReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}, from {} to {}",
+            plural(self.depths, "depth"),
+            self.shallowest,
+            self.deepest
+        )?;
 
-        let number: i32 = line.trim().parse().expect("Line not a number :(");
+        let run = self.longest_run;
+        write!(
+            f,
+            "Longest increasing run: {} on {}",
+            plural(run.len, "depth"),
+            lines(run.start, run.len)
+        )?;
+        if run.len > 1 {
+            write!(f, ", from {} to {}", run.first, run.last)?;
+        }
+        writeln!(f)?;
 
-        (if number > prev_num { total + 1 } else { total }, number)
-    });
+        match self.largest_jump {
+            Some(jump) => writeln!(
+                f,
+                "Largest jump: {:+} on {}, from {} to {}",
+                jump.change(),
+                lines(jump.at, 1),
+                jump.from,
+                jump.to
+            )?,
+            None => writeln!(f, "Largest jump: none, there is only one depth")?,
+        }
+
+        for summary in &self.windows {
+            write!(f, "Windows of {}: ", summary.size)?;
+            match (summary.smallest, summary.largest) {
+                (Some(smallest), Some(largest)) => {
+                    writeln!(
+                        f,
+                        "{}, {} up, {} down, {} level",
+                        plural(summary.windows, "sum"),
+                        summary.increases,
+                        summary.decreases,
+                        summary.unchanged
+                    )?;
+                    writeln!(
+                        f,
+                        "  smallest {} on {}, largest {} on {}",
+                        smallest.sum,
+                        lines(smallest.start, summary.size),
+                        largest.sum,
+                        lines(largest.start, summary.size)
+                    )?;
+                }
+                _ => writeln!(f, "none, there are fewer than {} depths", summary.size)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the sum of every window of each of `sizes` as CSV.
+pub fn write_window_sums(depths: &[i32], sizes: &[usize], mut writer: impl Write) -> Result<()> {
+    writeln!(writer, "window,first_line,last_line,sum,change")?;
+
+    for &size in sizes {
+        let sums = window_sums(depths, size);
+        for (start, &sum) in sums.iter().enumerate() {
+            let change = match start.checked_sub(1) {
+                Some(before) => (sum - sums[before]).to_string(),
+                None => String::new(),
+            };
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                size,
+                start + 1,
+                start + size,
+                sum,
+                change
+            )?;
+        }
+    }
 
-    println!("Total increasing numbers: {}", total);
+    writer.flush()?;
+    Ok(())
 }
 
-pub fn part2() {
-    let input = BufReader::new(File::open("input/day1.txt").expect("day 1 input missing"));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, solution, store::fixture};
+    use proptest::prelude::*;
+
+    const EXAMPLE: [i32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn window_increases_test() {
+        assert_eq!(window_increases(EXAMPLE, 1), Some(7));
+        assert_eq!(window_increases(EXAMPLE, 3), Some(5));
+        assert_eq!(window_increases(EXAMPLE, 10), Some(0));
+        assert_eq!(window_increases(EXAMPLE, 9), Some(1));
+
+        // Equal sums are not increases
+        assert_eq!(window_increases([1, 2, 3, 1], 3), Some(0));
+        assert_eq!(window_increases([-5_i64, -3, -4, -1], 2), Some(2));
+        assert_eq!(window_increases([3_u8, 2, 1], 1), Some(0));
+        // Sums that don't fit the values' type are compared all the same
+        let large = [i32::MAX, i32::MAX, 1, i32::MAX, i32::MAX];
+        assert_eq!(window_increases(large, 2), Some(1));
+    }
+
+    #[test]
+    fn shorter_than_window() {
+        assert_eq!(window_increases(Vec::<i32>::new(), 1), None);
+        assert_eq!(window_increases([1, 2], 3), None);
+        assert_eq!(window_increases(EXAMPLE, 11), None);
+        assert_eq!(window_increases([4], 1), Some(0));
+
+        assert!(Day1::part1(&vec![]).is_err());
+        assert_eq!(Day1::part1(&vec![4]).unwrap(), Answer::Number(0));
+        assert!(Day1::part2(&vec![1, 2]).is_err());
+        assert_eq!(Day1::part2(&vec![1, 2, 3]).unwrap(), Answer::Number(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_window() {
+        window_increases(EXAMPLE, 0);
+    }
+
+    #[test]
+    fn window_sums_test() {
+        assert_eq!(
+            window_sums(&EXAMPLE, 3),
+            [607, 618, 618, 617, 647, 716, 769, 792]
+        );
+        assert_eq!(window_sums(&EXAMPLE, 1).len(), 10);
+        assert_eq!(window_sums(&[i32::MAX, i32::MAX], 2), [2 * i32::MAX as i64]);
+        assert!(window_sums(&[1, 2], 3).is_empty());
+    }
+
+    #[test]
+    fn runs_and_jumps() {
+        // Two runs of 4, so the first one
+        assert_eq!(
+            longest_increasing_run(&EXAMPLE),
+            Some(Run {
+                start: 0,
+                len: 4,
+                first: 199,
+                last: 210
+            })
+        );
+        assert_eq!(
+            longest_increasing_run(&[3, 3, 2]),
+            Some(Run {
+                start: 0,
+                len: 1,
+                first: 3,
+                last: 3
+            })
+        );
+        assert_eq!(longest_increasing_run(&[]), None);
+
+        assert_eq!(
+            largest_jump(&EXAMPLE),
+            Some(Jump {
+                at: 6,
+                from: 207,
+                to: 240
+            })
+        );
+        assert_eq!(largest_jump(&[0, 5, 0]).unwrap().change(), 5);
+        assert_eq!(largest_jump(&[10, -20, 0]).unwrap().change(), -30);
+        assert_eq!(largest_jump(&[1]), None);
+    }
+
+    #[test]
+    fn window_summary() {
+        assert_eq!(
+            WindowSummary::new(&EXAMPLE, 3),
+            WindowSummary {
+                size: 3,
+                windows: 8,
+                increases: 5,
+                decreases: 1,
+                unchanged: 1,
+                smallest: Some(Window { start: 0, sum: 607 }),
+                largest: Some(Window { start: 7, sum: 792 }),
+            }
+        );
+
+        // The first of equal sums
+        let summary = WindowSummary::new(&[5, 1, 5], 1);
+        assert_eq!(summary.largest, Some(Window { start: 0, sum: 5 }));
+        assert_eq!(summary.smallest, Some(Window { start: 1, sum: 1 }));
 
-    let lines: Vec<i32> = input
-        .lines()
-        .map(|line| line.unwrap().trim().parse::<i32>().unwrap())
-        .collect();
+        let summary = WindowSummary::new(&[5, 1], 3);
+        assert_eq!((summary.windows, summary.largest), (0, None));
+    }
+
+    #[test]
+    fn sonar_report() {
+        let report = SonarReport::new(&EXAMPLE, &REPORT_WINDOWS).unwrap();
+        assert_eq!((report.shallowest, report.deepest), (199, 269));
+        assert_eq!(report.windows[0].increases, 7);
+        assert_eq!(report.windows[1].increases, 5);
+
+        let text = report.to_string();
+        assert!(text.starts_with("10 depths, from 199 to 269\n"));
+        assert!(text.contains("Longest increasing run: 4 depths on lines 1-4, from 199 to 210\n"));
+        assert!(text.contains("Largest jump: +33 on line 7, from 207 to 240\n"));
+        assert!(text.contains("Windows of 1: 10 sums, 7 up, 2 down, 0 level\n"));
+        assert!(text.contains("  smallest 607 on lines 1-3, largest 792 on lines 8-10\n"));
 
-    let offsets = [0, 1, 2];
+        let text = SonarReport::new(&[4], &[1, 3]).unwrap().to_string();
+        assert!(
+            text.starts_with("1 depth, from 4 to 4\nLongest increasing run: 1 depth on line 1\n")
+        );
+        assert!(text.contains("Windows of 1: 1 sum, 0 up"));
+        assert!(text.contains("Largest jump: none"));
+        assert!(text.contains("Windows of 3: none, there are fewer than 3 depths"));
+
+        assert!(SonarReport::new(&[], &REPORT_WINDOWS).is_err());
+    }
 
-    let mut prev_sum = 0;
-    let mut total = -1;
-    for i in 0..lines.len() - 2 {
-        let sum: i32 = offsets.iter().map(|offset| lines[i + offset]).sum();
+    #[test]
+    fn window_sums_csv() {
+        let mut csv = Vec::new();
+        write_window_sums(&[1, 2, 3], &[1, 2], &mut csv).unwrap();
 
-        if sum > prev_sum {
-            total += 1;
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "window,first_line,last_line,sum,change
+1,1,1,1,
+1,2,2,2,1
+1,3,3,3,1
+2,1,2,3,
+2,2,3,5,2
+"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_summed_windows(
+            depths in prop::collection::vec(-1000..1000_i64, 0..50),
+            size in 1..6_usize,
+        ) {
+            let sums = depths
+                .windows(size)
+                .map(|window| window.iter().sum::<i64>())
+                .collect::<Vec<i64>>();
+            let expected = (depths.len() >= size)
+                .then(|| sums.windows(2).filter(|pair| pair[1] > pair[0]).count());
+
+            prop_assert_eq!(window_increases(&depths, size), expected);
+        }
+
+        #[test]
+        fn summary_matches_counter(
+            depths in prop::collection::vec(-1000..1000_i32, 0..50),
+            size in 1..6_usize,
+        ) {
+            let summary = WindowSummary::new(&depths, size);
+            let sums = depths
+                .windows(size)
+                .map(|window| window.iter().map(|&depth| depth as i64).sum::<i64>())
+                .collect::<Vec<i64>>();
+
+            prop_assert_eq!(&window_sums(&depths, size), &sums);
+            prop_assert_eq!(summary.windows, sums.len());
+            prop_assert_eq!(
+                summary.increases + summary.decreases + summary.unchanged,
+                sums.len().saturating_sub(1)
+            );
+            if let Some(increases) = window_increases(&depths, size) {
+                prop_assert_eq!(summary.increases, increases);
+            }
+        }
+    }
+
+    #[test]
+    fn stream_matches_parse() {
+        for input in [
+            fixture(1, "example"),
+            generate::generate(1, 500, 3).unwrap(),
+        ] {
+            let streamed = solution::solve_stream::<Day1>(&mut input.as_bytes(), None).unwrap();
+
+            assert_eq!(
+                streamed.answers,
+                solution::solve::<Day1>(&input, None).unwrap()
+            );
         }
-        prev_sum = sum;
     }
 
-    println!("Total increasing groups of 3: {}", total);
+    #[test]
+    fn stream_errors() {
+        let err = solution::solve_stream::<Day1>(&mut "1\n2\nx\n".as_bytes(), None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 1 line 3 column 1: expected a depth (found \"x\")"
+        );
+
+        assert!(solution::solve_stream::<Day1>(&mut "1\n2\n".as_bytes(), Some(2)).is_err());
+        assert!(solution::solve_stream::<Day1>(&mut "".as_bytes(), Some(1)).is_err());
+    }
 }
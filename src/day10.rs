@@ -1,7 +1,12 @@
-use std::{convert::TryFrom, fs::File, io::BufRead, io::BufReader};
+use std::convert::TryFrom;
+
+use crate::{
+    error::{Error, Result},
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-enum Chunk {
+pub enum Chunk {
     Paren,
     Bracket,
     Brace,
@@ -23,7 +28,7 @@ impl TryFrom<char> for Chunk {
 }
 
 impl Chunk {
-    fn parse_end(c: char) -> Result<Chunk, &'static str> {
+    pub fn parse_end(c: char) -> Result<Chunk, &'static str> {
         match c {
             ')' => Ok(Chunk::Paren),
             ']' => Ok(Chunk::Bracket),
@@ -34,7 +39,7 @@ impl Chunk {
     }
 }
 
-fn error_points(chunk: Chunk) -> u32 {
+pub fn error_points(chunk: Chunk) -> u32 {
     match chunk {
         Chunk::Paren => 3,
         Chunk::Bracket => 57,
@@ -43,7 +48,7 @@ fn error_points(chunk: Chunk) -> u32 {
     }
 }
 
-fn error_points_2(chunk: Chunk) -> usize {
+pub fn error_points_2(chunk: Chunk) -> usize {
     match chunk {
         Chunk::Paren => 1,
         Chunk::Bracket => 2,
@@ -53,12 +58,13 @@ fn error_points_2(chunk: Chunk) -> usize {
 }
 
 #[derive(Debug, PartialEq)]
-enum ParseError {
+pub enum ParseError {
     Incomplete(Vec<Chunk>),
     Corrupt(Chunk),
+    Invalid(char),
 }
 
-fn parse_line(line: &str) -> Result<(), ParseError> {
+pub fn parse_line(line: &str) -> Result<(), ParseError> {
     let mut stack = Vec::new();
     for c in line.trim().chars() {
         if let Ok(chunk) = Chunk::try_from(c) {
@@ -72,7 +78,7 @@ fn parse_line(line: &str) -> Result<(), ParseError> {
                 }
             }
         } else {
-            unreachable!()
+            return Err(ParseError::Invalid(c));
         }
     }
 
@@ -83,52 +89,73 @@ fn parse_line(line: &str) -> Result<(), ParseError> {
     }
 }
 
-pub fn part1() {
-    let input = BufReader::new(File::open("input/day10.txt").unwrap());
-    let error_points: u32 = input
-        .lines()
-        .filter_map(|line| {
-            let line = line.expect("Invalid line");
-            match parse_line(&line) {
-                Ok(_) => None,
-                Err(err) => match err {
-                    ParseError::Incomplete(_) => None,
-                    ParseError::Corrupt(chunk) => Some(error_points(chunk)),
-                },
-            }
-        })
-        .sum();
-
-    println!("Total error points: {}", error_points);
-}
-
-fn score_incomplete(remaining: Vec<Chunk>) -> usize {
+pub fn score_incomplete(remaining: Vec<Chunk>) -> usize {
     remaining
         .iter()
         .rev()
         .fold(0, |total, &chunk| (total * 5) + error_points_2(chunk))
 }
 
-pub fn part2() {
-    let input = BufReader::new(File::open("input/day10.txt").unwrap());
-    let mut error_scores: Vec<usize> = input
-        .lines()
-        .filter_map(|line| {
-            let line = line.expect("Invalid line");
-            match parse_line(&line) {
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .map(|line| {
+                let bad = line
+                    .trim()
+                    .char_indices()
+                    .find(|&(_, c)| Chunk::try_from(c).is_err() && Chunk::parse_end(c).is_err());
+
+                if let Some((i, c)) = bad {
+                    let token = &line.trim()[i..i + c.len_utf8()];
+                    return Err(Error::parse(input, token, "expected a bracket"));
+                }
+
+                Ok(line.to_string())
+            })
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let points = input
+            .iter()
+            .filter_map(|line| match parse_line(line) {
+                Ok(_) => None,
+                Err(err) => match err {
+                    ParseError::Incomplete(_) | ParseError::Invalid(_) => None,
+                    ParseError::Corrupt(chunk) => Some(error_points(chunk)),
+                },
+            })
+            .sum::<u32>();
+
+        Ok(points.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let mut error_scores: Vec<usize> = input
+            .iter()
+            .filter_map(|line| match parse_line(line) {
                 Ok(_) => None,
                 Err(err) => match err {
                     ParseError::Incomplete(unmatched) => Some(score_incomplete(unmatched)),
-                    ParseError::Corrupt(_) => None,
+                    ParseError::Corrupt(_) | ParseError::Invalid(_) => None,
                 },
-            }
-        })
-        .collect();
+            })
+            .collect();
 
-    error_scores.sort_unstable();
-    let mid = error_scores[error_scores.len() / 2];
+        if error_scores.is_empty() {
+            return Err(Error::unsolvable("no incomplete lines"));
+        }
 
-    println!("Middle error score: {}", mid);
+        error_scores.sort_unstable();
+        Ok(error_scores[error_scores.len() / 2].into())
+    }
 }
 
 #[cfg(test)]
@@ -207,6 +234,8 @@ mod tests {
                 Chunk::Bracket
             ])
         );
+
+        assert_eq!(parse_line("[(x)]"), Err(ParseError::Invalid('x')));
     }
 
     #[test]
@@ -219,7 +248,7 @@ mod tests {
         if let ParseError::Incomplete(remaining) = error {
             assert_eq!(score_incomplete(remaining), 294);
         } else {
-            assert!(false);
+            unreachable!();
         }
     }
 }
@@ -1,173 +1,178 @@
-use std::{fs::File, io::BufRead, io::BufReader};
-
-struct Board {
-    data: Vec<i32>,
-    width: u32,
+use crate::{
+    error::{Error, Result},
+    grid::Grid,
+    render::{self, Frame, Render, Rgb},
+    solution::{Answer, Solution},
+};
+
+/// How long part 2 waits for every octopus to flash at once. Puzzle inputs
+/// synchronise within a few hundred steps, but some grids never do.
+pub const MAX_STEPS: usize = 100_000;
+
+/// How many steps an animation shows if the octopuses don't synchronise first.
+pub const MAX_FRAMES: usize = 1_000;
+
+const FLASH: Rgb = [255, 250, 200];
+const CHARGED: Rgb = [40, 140, 170];
+
+#[derive(Clone)]
+pub struct Board {
+    pub energy: Grid<i32>,
 }
 
 impl Board {
-    fn index(&self, (x, y): (u32, u32)) -> usize {
-        ((y * self.width) + x) as usize
-    }
-    fn coord(&self, index: usize) -> (u32, u32) {
-        (index as u32 % self.width, index as u32 / self.width)
-    }
-
-    fn neighbours(&self, index: usize) -> Vec<usize> {
-        let masks = [
-            (-1, -1),
-            (0, -1),
-            (1, -1),
-            (-1, 0),
-            (1, 0),
-            (-1, 1),
-            (0, 1),
-            (1, 1),
-        ];
-        let height = self.data.len() as u32 / self.width;
-        let (x, y) = self.coord(index);
-        masks
-            .iter()
-            .map(|&(a, b)| (x as i32 + a, y as i32 + b))
-            .filter(|&(x, y)| x >= 0 && (x as u32) < self.width && y >= 0 && (y as u32) < height)
-            .map(|(x, y)| self.index((x as u32, y as u32)))
-            .filter(|&i| self.data[i] >= 0)
+    pub fn neighbours(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        self.energy
+            .neighbours8(pos)
+            .filter(|&neighbour| self.energy[neighbour] >= 0)
             .collect()
     }
 
-    fn step(&mut self) -> usize {
+    pub fn step(&mut self) -> usize {
         let mut num_flashes = 0;
         // Stage 1 - Increase power level
-        for i in 0..self.data.len() {
-            self.data[i] += 1;
-        }
+        self.energy.iter_mut().for_each(|energy| *energy += 1);
 
         let mut check_9s = true;
         while check_9s {
             check_9s = false;
 
-            for i in 0..self.data.len() {
-                if self.data[i] > 9 {
+            for pos in self.energy.positions() {
+                if self.energy[pos] > 9 {
                     // Flash
                     check_9s = true; // check if more >9s created
 
-                    self.data[i] = -1; // Sentinel
+                    self.energy[pos] = -1; // Sentinel
                     num_flashes += 1;
 
-                    let neighbours = self.neighbours(i);
-                    neighbours.iter().for_each(|&j| self.data[j] += 1);
+                    let neighbours = self.neighbours(pos);
+                    neighbours.iter().for_each(|&j| self.energy[j] += 1);
                 }
             }
         }
         // Reset flashed
-        for i in 0..self.data.len() {
-            if self.data[i] == -1 {
-                self.data[i] = 0;
-            }
-        }
+        self.energy
+            .iter_mut()
+            .filter(|energy| **energy == -1)
+            .for_each(|energy| *energy = 0);
 
         num_flashes
     }
 
-    fn all_flashing(&self) -> bool {
-        self.data.iter().all(|&x| x == 0)
+    pub fn all_flashing(&self) -> bool {
+        self.energy.iter().all(|&x| x == 0)
+    }
+
+    /// The first step after which every octopus has just flashed, if that
+    /// happens within `max_steps`.
+    pub fn first_sync(&self, max_steps: usize) -> Option<usize> {
+        let mut board = self.clone();
+
+        for step in 0..=max_steps {
+            if board.all_flashing() {
+                return Some(step);
+            }
+            board.step();
+        }
+
+        None
     }
 }
 
-pub fn part1() {
-    let input = BufReader::new(File::open("input/day11.txt").unwrap());
+pub struct Day11;
 
-    let width = 10;
-    let height = 10;
-    let mut data: Vec<i32> = Vec::new();
-    data.reserve(width * height);
+impl Solution for Day11 {
+    const DAY: u8 = 11;
 
-    input.lines().for_each(|line| {
-        let line = line.expect("invalid line");
-        line.trim()
-            .chars()
-            .map(|c| c.to_digit(10).expect("invalid digit") as i32)
-            .for_each(|x| data.push(x));
-    });
+    type Input = Board;
 
-    let mut board = Board {
-        data,
-        width: width as u32,
-    };
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Board {
+            energy: Grid::parse_digits(input)?.map(|&energy| energy as i32),
+        })
+    }
 
-    let num_flashes = (0..100).fold(0, |count, _| count + board.step());
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let mut board = input.clone();
 
-    println!("Number of flashes is {}", num_flashes);
-}
-pub fn part2() {
-    let input = BufReader::new(File::open("input/day11.txt").unwrap());
-
-    let width = 10;
-    let height = 10;
-    let mut data: Vec<i32> = Vec::new();
-    data.reserve(width * height);
-
-    input.lines().for_each(|line| {
-        let line = line.expect("invalid line");
-        line.trim()
-            .chars()
-            .map(|c| c.to_digit(10).expect("invalid digit") as i32)
-            .for_each(|x| data.push(x));
-    });
-
-    let mut board = Board {
-        data,
-        width: width as u32,
-    };
-
-    let mut step = 0;
-    while !board.all_flashing() {
-        step += 1;
-        board.step();
+        let num_flashes = (0..100).fold(0, |count, _| count + board.step());
+
+        Ok(num_flashes.into())
     }
 
-    println!("All flashing after step {}", step);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let step = input.first_sync(MAX_STEPS).ok_or_else(|| {
+            Error::unsolvable(format!(
+                "the octopuses don't all flash at once within {} steps",
+                MAX_STEPS
+            ))
+        })?;
+
+        Ok(step.into())
+    }
+}
+
+/// The octopuses at the start and after each step until they all flash at once,
+/// brighter as they charge up, with the ones that just flashed glowing.
+impl Render for Day11 {
+    fn frames(input: &Self::Input) -> Result<Vec<Frame>> {
+        let mut board = input.clone();
+        let mut frames = Vec::new();
+
+        for step in 0..=MAX_FRAMES {
+            frames.push(render::draw(&board.energy, |_, &energy| {
+                if energy == 0 && step > 0 {
+                    FLASH
+                } else {
+                    render::blend(render::BACKGROUND, CHARGED, energy as f64 / 9.0)
+                }
+            }));
+
+            if step > 0 && board.all_flashing() {
+                break;
+            }
+            board.step();
+        }
+
+        Ok(frames)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store::fixture;
+
+    fn fixture_board(name: &str) -> Board {
+        Day11::parse(&fixture(11, name)).unwrap()
+    }
 
     #[test]
     fn step_test() {
-        let initial_data = vec![
-            5, 4, 8, 3, 1, 4, 3, 2, 2, 3, 2, 7, 4, 5, 8, 5, 4, 7, 1, 1, 5, 2, 6, 4, 5, 5, 6, 1, 7,
-            3, 6, 1, 4, 1, 3, 3, 6, 1, 4, 6, 6, 3, 5, 7, 3, 8, 5, 4, 7, 8, 4, 1, 6, 7, 5, 2, 4, 6,
-            4, 5, 2, 1, 7, 6, 8, 4, 1, 7, 2, 1, 6, 8, 8, 2, 8, 8, 1, 1, 3, 4, 4, 8, 4, 6, 8, 4, 8,
-            5, 5, 4, 5, 2, 8, 3, 7, 5, 1, 5, 2, 6,
-        ];
-        let width = 10;
-
-        let mut board = Board {
-            data: initial_data,
-            width,
-        };
+        let mut board = fixture_board("example");
 
         board.step();
-        assert_eq!(
-            board.data,
-            vec![
-                6, 5, 9, 4, 2, 5, 4, 3, 3, 4, 3, 8, 5, 6, 9, 6, 5, 8, 2, 2, 6, 3, 7, 5, 6, 6, 7, 2,
-                8, 4, 7, 2, 5, 2, 4, 4, 7, 2, 5, 7, 7, 4, 6, 8, 4, 9, 6, 5, 8, 9, 5, 2, 7, 8, 6, 3,
-                5, 7, 5, 6, 3, 2, 8, 7, 9, 5, 2, 8, 3, 2, 7, 9, 9, 3, 9, 9, 2, 2, 4, 5, 5, 9, 5, 7,
-                9, 5, 9, 6, 6, 5, 6, 3, 9, 4, 8, 6, 2, 6, 3, 7,
-            ]
-        );
+        assert_eq!(board.energy, fixture_board("after-step1").energy);
 
         board.step();
-        assert_eq!(
-            board.data,
-            vec![
-                8, 8, 0, 7, 4, 7, 6, 5, 5, 5, 5, 0, 8, 9, 0, 8, 7, 0, 5, 4, 8, 5, 9, 7, 8, 8, 9, 6,
-                0, 8, 8, 4, 8, 5, 7, 6, 9, 6, 0, 0, 8, 7, 0, 0, 9, 0, 8, 8, 0, 0, 6, 6, 0, 0, 0, 8,
-                8, 9, 8, 9, 6, 8, 0, 0, 0, 0, 5, 9, 4, 3, 0, 0, 0, 0, 0, 0, 7, 4, 5, 6, 9, 0, 0, 0,
-                0, 0, 0, 8, 7, 6, 8, 7, 0, 0, 0, 0, 6, 8, 4, 8,
-            ]
-        );
+        assert_eq!(board.energy, fixture_board("after-step2").energy);
+    }
+
+    #[test]
+    fn first_sync_test() {
+        let board = fixture_board("example");
+
+        assert_eq!(board.first_sync(1000), Some(195));
+        assert_eq!(board.first_sync(194), None);
+        assert_eq!(Day11::parse("00\n00").unwrap().first_sync(0), Some(0));
+    }
+
+    #[test]
+    fn parse_dimensions() {
+        let board = Day11::parse("111\n191\n").unwrap();
+        assert_eq!(board.energy.dimensions(), (3, 2));
+
+        assert!(Day11::parse("111\n19\n").is_err());
+        assert!(Day11::parse("").is_err());
     }
 }
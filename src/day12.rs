@@ -1,39 +1,81 @@
-use std::{collections::HashMap, fs::File, io::BufReader, io::Read};
+use std::{collections::HashMap, convert::TryFrom};
 
-struct CaveSystem {
+use crate::{
+    error::{Error, Result},
+    solution::{Answer, Solution},
+};
+
+pub struct CaveSystem {
     paths: HashMap<String, Vec<String>>,
 }
 
-impl From<&str> for CaveSystem {
-    fn from(s: &str) -> Self {
+fn is_big(cave: &str) -> bool {
+    cave.chars().all(|c| c.is_ascii_uppercase())
+}
+
+fn check_cave(line: &str, cave: &str) -> Result<()> {
+    if cave.is_empty() || !(is_big(cave) || cave.chars().all(|c| c.is_ascii_lowercase())) {
+        return Err(Error::parse(
+            line,
+            cave,
+            "expected a cave name in all upper or all lower case",
+        ));
+    }
+
+    Ok(())
+}
+
+impl TryFrom<&str> for CaveSystem {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
         let mut state = HashMap::new();
-        s.lines()
-            .filter_map(|line| line.split_once('-'))
-            .for_each(|(start, end)| {
-                let start = start.to_string();
-                let end = end.to_string();
+        for line in s.lines().map(str::trim) {
+            let (start, end) = line
+                .split_once('-')
+                .ok_or_else(|| Error::parse(s, line, "expected a connection like 'a-b'"))?;
+
+            check_cave(line, start).map_err(|err| err.within(s, line))?;
+            check_cave(line, end).map_err(|err| err.within(s, line))?;
+            if is_big(start) && is_big(end) {
+                return Err(Error::parse(
+                    s,
+                    line,
+                    "two big caves can't be connected, there would be infinitely many paths",
+                ));
+            }
 
-                let entry = state.entry(start.clone()).or_insert_with(Vec::new);
+            let start = start.to_string();
+            let end = end.to_string();
 
-                if !entry.contains(&end) && end != "start" && start != "end" {
-                    entry.push(end.clone());
-                }
+            let entry = state.entry(start.clone()).or_insert_with(Vec::new);
 
-                let entry = state.entry(end.clone()).or_insert_with(Vec::new);
-                if !entry.contains(&start) && start != "start" && end != "end" {
-                    entry.push(start);
-                }
-            });
+            if !entry.contains(&end) && end != "start" && start != "end" {
+                entry.push(end.clone());
+            }
 
-        CaveSystem { paths: state }
+            let entry = state.entry(end.clone()).or_insert_with(Vec::new);
+            if !entry.contains(&start) && start != "start" && end != "end" {
+                entry.push(start);
+            }
+        }
+
+        if !state.contains_key("start") {
+            return Err(Error::parse(s, &s[s.len()..], "expected a start cave"));
+        }
+
+        Ok(CaveSystem { paths: state })
     }
 }
 
 impl CaveSystem {
-    fn paths(&self, duplicate_once: bool) -> Vec<Vec<String>> {
+    pub fn paths(&self, duplicate_once: bool) -> Vec<Vec<String>> {
         let mut paths = Vec::new();
 
-        let mut next_paths = vec![self.paths["start"].iter().peekable()];
+        let mut next_paths = match self.paths.get("start") {
+            Some(start) => vec![start.iter().peekable()],
+            None => return paths,
+        };
 
         let mut path = vec!["start".to_string()];
 
@@ -76,32 +118,24 @@ impl CaveSystem {
     }
 }
 
-pub fn part1() {
-    let mut input = BufReader::new(File::open("input/day12.txt").expect("Input file should exist"));
+pub struct Day12;
 
-    let mut input_str = String::new();
-    input
-        .read_to_string(&mut input_str)
-        .expect("Should read input fine");
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    let cave_system = CaveSystem::from(&input_str as &str);
-    let num_paths = cave_system.paths(false).len();
+    type Input = CaveSystem;
 
-    println!("Number of paths: {}", num_paths);
-}
-
-pub fn part2() {
-    let mut input = BufReader::new(File::open("input/day12.txt").expect("Input file should exist"));
-
-    let mut input_str = String::new();
-    input
-        .read_to_string(&mut input_str)
-        .expect("Should read input fine");
+    fn parse(input: &str) -> Result<Self::Input> {
+        CaveSystem::try_from(input)
+    }
 
-    let cave_system = CaveSystem::from(&input_str as &str);
-    let num_paths = cave_system.paths(true).len();
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(input.paths(false).len().into())
+    }
 
-    println!("Number of paths: {}", num_paths);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(input.paths(true).len().into())
+    }
 }
 
 #[cfg(test)]
@@ -118,7 +152,7 @@ b-d
 A-end
 b-end";
 
-        let caves = CaveSystem::from(input);
+        let caves = CaveSystem::try_from(input).unwrap();
 
         let paths = caves.paths(false);
         println!("{:?}", paths);
@@ -135,7 +169,7 @@ b-d
 A-end
 b-end";
 
-        let caves = CaveSystem::from(input);
+        let caves = CaveSystem::try_from(input).unwrap();
 
         let paths = caves.paths(true);
         println!("{:?}", paths);
@@ -155,7 +189,7 @@ kj-sa
 kj-HN
 kj-dc";
 
-        let caves = CaveSystem::from(input);
+        let caves = CaveSystem::try_from(input).unwrap();
 
         let paths = caves.paths(false);
         assert_eq!(paths.len(), 19);
@@ -182,7 +216,7 @@ zg-he
 pj-fs
 start-RW";
 
-        let caves = CaveSystem::from(input);
+        let caves = CaveSystem::try_from(input).unwrap();
 
         let paths = caves.paths(false);
         assert_eq!(paths.len(), 226);
@@ -1,153 +1,262 @@
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufReader, Read},
+use std::collections::HashSet;
+
+use crate::{
+    error::{parse_token, Error, Result},
+    grid::Grid,
+    render::{self, Frame, Render, Rgb},
+    solution::{Answer, Solution},
 };
 
+const DOT: Rgb = [255, 215, 80];
+const FOLD_LINE: Rgb = [220, 40, 60];
+
+/// The (x, y) positions of the dots on the paper.
+pub type Dots = HashSet<(u32, u32)>;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
-enum Inst {
+pub enum Inst {
     X(u32),
     Y(u32),
 }
 
-fn fold_set(dots: &HashSet<(u32, u32)>, fold: Inst) -> HashSet<(u32, u32)> {
-    let mut new_set = HashSet::new();
+/// The width and height of the paper.
+pub type Size = (u32, u32);
+
+/// The smallest paper with all of `dots` on it.
+pub fn paper_size(dots: &Dots) -> Size {
+    dots.iter().fold((0, 0), |(width, height), &(x, y)| {
+        (
+            width.max(x.saturating_add(1)),
+            height.max(y.saturating_add(1)),
+        )
+    })
+}
 
-    match fold {
-        Inst::X(fold) => {
-            for &(x, y) in dots {
-                let x = if x > fold {
-                    let x = fold - (x % fold);
-                    if x == fold {
-                        0
-                    } else {
-                        x
-                    }
-                } else {
-                    x
-                };
-                new_set.insert((x, y));
-            }
-        }
-        Inst::Y(fold) => {
-            for &(x, y) in dots {
-                let y = if y > fold {
-                    let y = fold - (y % fold);
-                    if y == fold {
-                        0
-                    } else {
-                        y
-                    }
-                } else {
-                    y
-                };
-                new_set.insert((x, y));
-            }
+/// Mirrors `value` across the fold line at `fold`. Values past the fold land the
+/// same distance before it, which is off the paper if the flap is the longer side.
+fn mirror(value: u32, fold: u32) -> i64 {
+    if value > fold {
+        2 * fold as i64 - value as i64
+    } else {
+        value as i64
+    }
+}
+
+/// How far the flap of a paper `length` long hangs past the paper's edge when
+/// folded at `fold`, which is 0 unless the flap is the longer side.
+fn overhang(length: u32, fold: u32) -> i64 {
+    (length as i64 - 1 - 2 * fold as i64).max(0)
+}
+
+/// The size of a paper of `size` once folded.
+pub fn fold_size((width, height): Size, fold: Inst) -> Size {
+    let folded = |length: u32, fold: u32| {
+        if length > fold {
+            fold + overhang(length, fold) as u32
+        } else {
+            length
         }
+    };
+
+    match fold {
+        Inst::X(fold) => (folded(width, fold), height),
+        Inst::Y(fold) => (width, folded(height, fold)),
     }
+}
 
-    new_set
+/// Folds `dots` on a paper of `size`, which they must all be on. Dots on the
+/// fold line are lost in the crease.
+pub fn fold_set(
+    dots: &HashSet<(u32, u32)>,
+    (width, height): Size,
+    fold: Inst,
+) -> HashSet<(u32, u32)> {
+    // A flap longer than the rest of the paper hangs past the edge, so move the
+    // paper back by the overhang to keep every position non-negative
+    let fold_at =
+        |value: u32, length: u32, fold: u32| (mirror(value, fold) + overhang(length, fold)) as u32;
+
+    dots.iter()
+        .filter(|&&(x, y)| fold != Inst::X(x) && fold != Inst::Y(y))
+        .map(|&(x, y)| match fold {
+            Inst::X(fold) => (fold_at(x, width, fold), y),
+            Inst::Y(fold) => (x, fold_at(y, height, fold)),
+        })
+        .collect()
 }
 
-fn parse_set(input: &str) -> HashSet<(u32, u32)> {
+pub fn parse_set(input: &str) -> Result<HashSet<(u32, u32)>> {
     let mut dots = HashSet::new();
 
-    input
-        .lines()
-        .map(|line| {
-            line.split_once(',')
-                .expect("All lines should be the same format")
-        })
-        .map(|(x, y)| {
-            (
-                x.parse().expect("Should be parseable"),
-                y.parse().expect("Should be parseable"),
-            )
-        })
-        .for_each(|dot| {
-            dots.insert(dot);
-        });
-    dots
+    for line in input.lines().map(str::trim) {
+        let (x, y) = line
+            .split_once(',')
+            .ok_or_else(|| Error::parse(input, line, "expected a dot like 'x,y'"))?;
+
+        dots.insert((
+            parse_token(input, x, "a number")?,
+            parse_token(input, y, "a number")?,
+        ));
+    }
+
+    Ok(dots)
 }
 
-fn parse_instruction(input: &str) -> Inst {
-    let (_, num) = input.split_once('=').expect("Bad format");
-    let num = num.parse().expect("Should be a number");
-    if input.find('y').is_some() {
-        Inst::Y(num)
-    } else {
-        Inst::X(num)
+pub fn parse_instruction(input: &str) -> Result<Inst> {
+    let fold = input
+        .trim()
+        .strip_prefix("fold along ")
+        .ok_or_else(|| Error::parse(input, input.trim(), "expected 'fold along'"))?;
+
+    let (axis, num) = fold
+        .split_once('=')
+        .ok_or_else(|| Error::parse(input, fold, "expected an axis like 'x=5'"))?;
+    let num = parse_token(input, num, "a number")?;
+    if num == 0 {
+        return Err(Error::parse(
+            input,
+            fold,
+            "expected a fold after the first row or column",
+        ));
+    }
+
+    match axis {
+        "x" => Ok(Inst::X(num)),
+        "y" => Ok(Inst::Y(num)),
+        _ => Err(Error::parse(input, axis, "expected the x or y axis")),
     }
 }
 
-fn parse_input(input: &str) -> (HashSet<(u32, u32)>, Vec<Inst>) {
-    let (dots, insts) = input.trim().split_once("\n\n").expect("Input format wrong");
+pub fn parse_input(input: &str) -> Result<(Dots, Vec<Inst>)> {
+    let (dots, insts) = input.trim().split_once("\n\n").ok_or_else(|| {
+        Error::parse(
+            input,
+            &input[input.len()..],
+            "expected fold instructions after the dots",
+        )
+    })?;
+
+    let instructions = insts
+        .lines()
+        .map(|line| parse_instruction(line).map_err(|err| err.within(input, line)))
+        .collect::<Result<Vec<Inst>>>()?;
 
-    let instructions = insts.lines().map(parse_instruction).collect();
+    let dots = parse_set(dots).map_err(|err| err.within(input, dots))?;
 
-    (parse_set(dots), instructions)
+    Ok((dots, instructions))
 }
 
-fn print_board(dots: &HashSet<(u32, u32)>) {
-    let max_x = dots
-        .iter()
-        .map(|&(x, _)| x)
-        .max()
-        .expect("Should have a max");
+pub fn render_board(dots: &HashSet<(u32, u32)>) -> String {
+    let max_x = match dots.iter().map(|&(x, _)| x).max() {
+        Some(max_x) => max_x,
+        None => return String::new(),
+    };
 
-    let max_y = dots
-        .iter()
-        .map(|&(_, y)| y)
-        .max()
-        .expect("Should have a max");
+    let max_y = dots.iter().map(|&(_, y)| y).max().unwrap_or(0);
 
+    let mut board = String::new();
     for y in 0..=max_y {
         for x in 0..=max_x {
             if dots.contains(&(x, y)) {
-                print!("#");
+                board.push('#');
             } else {
-                print!(".");
+                board.push('.');
             }
         }
-        println!();
+        board.push('\n');
     }
-    println!();
+
+    board
 }
 
-pub fn part1() {
-    let mut input = BufReader::new(File::open("input/day13.txt").expect("Input file should exist"));
+pub fn print_board(dots: &HashSet<(u32, u32)>) {
+    println!("{}", render_board(dots));
+}
 
-    let mut input_string = String::new();
-    input
-        .read_to_string(&mut input_string)
-        .expect("Should read file fine");
+/// The paper with its dots, and the line of the next fold if there is one.
+pub fn draw_paper(dots: &Dots, fold: Option<Inst>) -> Frame {
+    let (mut width, mut height) = match fold {
+        Some(Inst::X(x)) => (x as usize + 1, 1),
+        Some(Inst::Y(y)) => (1, y as usize + 1),
+        None => (1, 1),
+    };
+    for &(x, y) in dots {
+        width = width.max(x as usize + 1);
+        height = height.max(y as usize + 1);
+    }
 
-    let (dots, instructions) = parse_input(&input_string);
-    let new_dots = fold_set(&dots, instructions[0]);
+    let paper = Grid::new(vec![(); width * height], width);
+    render::draw(&paper, |(x, y), _| {
+        let (x, y) = (x as u32, y as u32);
 
-    println!("Number of dots after fold: {}", new_dots.len());
+        match fold {
+            _ if dots.contains(&(x, y)) => DOT,
+            Some(Inst::X(line)) if line == x => FOLD_LINE,
+            Some(Inst::Y(line)) if line == y => FOLD_LINE,
+            _ => render::BACKGROUND,
+        }
+    })
 }
-pub fn part2() {
-    let mut input = BufReader::new(File::open("input/day13.txt").expect("Input file should exist"));
 
-    let mut input_string = String::new();
-    input
-        .read_to_string(&mut input_string)
-        .expect("Should read file fine");
+pub struct Day13;
 
-    let (dots, instructions) = parse_input(&input_string);
+impl Solution for Day13 {
+    const DAY: u8 = 13;
 
-    let final_board = instructions
-        .iter()
-        .fold(dots, |dots, &inst| fold_set(&dots, inst));
+    type Input = (Dots, Vec<Inst>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let (dots, instructions) = input;
+        let first = instructions
+            .first()
+            .ok_or_else(|| Error::unsolvable("no fold instructions"))?;
+        let new_dots = fold_set(dots, paper_size(dots), *first);
+
+        Ok(new_dots.len().into())
+    }
 
-    println!("Final board: ");
-    print_board(&final_board);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let (dots, instructions) = input;
+
+        let (final_board, _) = instructions
+            .iter()
+            .fold((dots.clone(), paper_size(dots)), |(dots, size), &inst| {
+                (fold_set(&dots, size, inst), fold_size(size, inst))
+            });
+
+        Ok(Answer::Grid(
+            render_board(&final_board).trim_end().to_string(),
+        ))
+    }
+}
+
+/// The paper before each fold, with the fold line, and after the last one.
+impl Render for Day13 {
+    fn frames(input: &Self::Input) -> Result<Vec<Frame>> {
+        let (dots, instructions) = input;
+        let mut dots = dots.clone();
+        let mut size = paper_size(&dots);
+        let mut frames = Vec::new();
+
+        for &fold in instructions {
+            frames.push(draw_paper(&dots, Some(fold)));
+            dots = fold_set(&dots, size, fold);
+            size = fold_size(size, fold);
+        }
+        frames.push(draw_paper(&dots, None));
+
+        Ok(frames)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn parse_test() {
@@ -170,7 +279,7 @@ mod tests {
 8,10
 9,0";
 
-        let dots = parse_set(input);
+        let dots = parse_set(input).unwrap();
 
         assert_eq!(dots.len(), 18);
     }
@@ -178,14 +287,26 @@ mod tests {
     #[test]
     fn parse_instruction_test() {
         let inst = "fold along y=7";
-        let inst = parse_instruction(inst);
+        let inst = parse_instruction(inst).unwrap();
         assert_eq!(inst, Inst::Y(7));
 
         let inst = "fold along x=5";
-        let inst = parse_instruction(inst);
+        let inst = parse_instruction(inst).unwrap();
         assert_eq!(inst, Inst::X(5));
     }
 
+    #[test]
+    fn draw_paper_test() {
+        let dots = Dots::from([(0, 0), (2, 1)]);
+        let frame = draw_paper(&dots, Some(Inst::X(4)));
+
+        assert_eq!(frame.dimensions(), (5, 2));
+        assert_eq!(frame[(2, 1)], DOT);
+        assert_eq!(frame[(4, 0)], FOLD_LINE);
+        assert_eq!(frame[(1, 1)], render::BACKGROUND);
+        assert_eq!(draw_paper(&Dots::new(), None).dimensions(), (1, 1));
+    }
+
     #[test]
     fn parse_input_test() {
         let input = "6,10
@@ -210,7 +331,7 @@ mod tests {
 fold along y=7
 fold along x=5";
 
-        let (dots, instructions) = parse_input(input);
+        let (dots, instructions) = parse_input(input).unwrap();
 
         assert_eq!(dots.len(), 18);
         assert_eq!(instructions.len(), 2);
@@ -240,20 +361,121 @@ fold along x=5";
 fold along y=7
 fold along x=5";
 
-        let (dots, instructions) = parse_input(input);
+        let (dots, instructions) = parse_input(input).unwrap();
 
         print_board(&dots);
 
         assert_eq!(dots.len(), 18);
 
-        let new_dots = fold_set(&dots, instructions[0]);
+        let size = paper_size(&dots);
+        assert_eq!(size, (11, 15));
+        let new_dots = fold_set(&dots, size, instructions[0]);
         print_board(&new_dots);
 
         assert_eq!(new_dots.len(), 17);
 
-        let new_dots = fold_set(&new_dots, instructions[1]);
+        let size = fold_size(size, instructions[0]);
+        assert_eq!(size, (11, 7));
+        let new_dots = fold_set(&new_dots, size, instructions[1]);
         print_board(&new_dots);
 
         assert_eq!(new_dots.len(), 16);
     }
+
+    #[test]
+    fn fold_long_flap() {
+        // The flap past x=2 is longer than the paper left of it
+        let dots = HashSet::from([(0, 0), (1, 0), (7, 0)]);
+
+        assert_eq!(
+            fold_set(&dots, paper_size(&dots), Inst::X(2)),
+            HashSet::from([(3, 0), (4, 0), (0, 0)])
+        );
+    }
+
+    #[test]
+    fn fold_long_flap_without_edge_dot() {
+        // The paper goes to x=7, but the flap's last dot is at x=6, so the shift
+        // comes from the paper's edge rather than the dots
+        let dots = HashSet::from([(0, 0), (6, 0)]);
+
+        assert_eq!(
+            fold_set(&dots, (8, 1), Inst::X(2)),
+            HashSet::from([(3, 0), (1, 0)])
+        );
+        assert_eq!(fold_size((8, 1), Inst::X(2)), (5, 1));
+        assert_eq!(
+            fold_set(&HashSet::from([(2, 0), (6, 0)]), (8, 1), Inst::X(2)),
+            HashSet::from([(1, 0)])
+        );
+        assert_eq!(fold_size((8, 1), Inst::X(9)), (8, 1));
+    }
+
+    fn dots_and_fold() -> impl Strategy<Value = (Dots, Inst)> {
+        (1..40_u32, any::<bool>()).prop_flat_map(|(fold, along_x)| {
+            let dots = prop::collection::hash_set((0..120_u32, 0..120_u32), 0..60);
+            let fold = if along_x {
+                Inst::X(fold)
+            } else {
+                Inst::Y(fold)
+            };
+
+            (dots, Just(fold))
+        })
+    }
+
+    /// Picks one coordinate of a dot.
+    type Axis = fn(&(u32, u32)) -> u32;
+
+    proptest! {
+        #[test]
+        fn folding_never_adds_dots((dots, fold) in dots_and_fold()) {
+            prop_assert!(fold_set(&dots, paper_size(&dots), fold).len() <= dots.len());
+        }
+
+        #[test]
+        fn folding_keeps_distances((dots, fold) in dots_and_fold()) {
+            // Dots on the flap keep their distances from each other along the fold axis
+            let (axis, line): (Axis, u32) = match fold {
+                Inst::X(line) => (|&(x, _)| x, line),
+                Inst::Y(line) => (|&(_, y)| y, line),
+            };
+            let flap = dots.iter().filter(|dot| axis(dot) > line).collect::<Vec<_>>();
+
+            for pair in flap.windows(2) {
+                let before = axis(pair[0]).abs_diff(axis(pair[1]));
+                let both = HashSet::from([*pair[0], *pair[1]]);
+                let folded = fold_set(&both, paper_size(&both), fold);
+                let after = match folded.iter().map(axis).collect::<Vec<u32>>().as_slice() {
+                    [a, b] => a.abs_diff(*b),
+                    _ => 0,
+                };
+
+                prop_assert_eq!(before, after);
+            }
+        }
+
+        #[test]
+        fn folding_stays_on_paper((dots, fold) in dots_and_fold()) {
+            let size = paper_size(&dots);
+            let (width, height) = fold_size(size, fold);
+
+            for (x, y) in fold_set(&dots, size, fold) {
+                prop_assert!(x < width && y < height);
+            }
+        }
+
+        #[test]
+        fn folding_half_lands_before_line((dots, fold) in dots_and_fold()) {
+            let (axis, line): (Axis, u32) = match fold {
+                Inst::X(line) => (|&(x, _)| x, line),
+                Inst::Y(line) => (|&(_, y)| y, line),
+            };
+            let half = dots.iter().filter(|dot| axis(dot) <= 2 * line).copied().collect::<Dots>();
+
+            for dot in fold_set(&half, paper_size(&half), fold) {
+                prop_assert!(axis(&dot) <= line);
+            }
+        }
+    }
 }
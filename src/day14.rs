@@ -1,13 +1,14 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufReader, Read},
+use std::collections::HashMap;
+
+use crate::{
+    error::{Error, Result},
+    solution::{Answer, Solution},
 };
 
-type Rule = ((char, char), char);
-type RuleSet = HashMap<(char, char), char>;
+pub type Rule = ((char, char), char);
+pub type RuleSet = HashMap<(char, char), char>;
 
-fn run_rules(
+pub fn run_rules(
     input: &HashMap<(char, char), usize>,
     rules: &RuleSet,
     num_letters: &mut HashMap<char, usize>,
@@ -23,74 +24,69 @@ fn run_rules(
 
             (*num_letters.entry(rule_out).or_insert(0)) += count;
         } else {
-            panic!();
+            // No rule matches, so nothing is inserted between the pair
+            *output.entry((a, b)).or_insert(0) += count;
         }
     }
 
     output
 }
 
-fn parse_initial(s: &str) -> HashMap<(char, char), usize> {
+pub fn parse_initial(s: &str) -> HashMap<(char, char), usize> {
     let mut output = HashMap::new();
-    for i in 0..(s.len() - 1) {
-        let mut chars = s[i..=(i + 1)].chars();
-        let first = chars.next().expect("At least 1 char");
-        let second = chars.next().expect("At least 2 char");
-        debug_assert!(chars.next().is_none());
-
-        let entry = output.entry((first, second)).or_insert(0_usize);
+    let chars = s.chars().collect::<Vec<char>>();
+    for pair in chars.windows(2) {
+        let entry = output.entry((pair[0], pair[1])).or_insert(0_usize);
         *entry += 1;
     }
 
     output
 }
 
-fn parse_rule(s: &str) -> Rule {
-    let (input, output) = s.split_once("->").expect("Rule should be well-formed");
+/// Parses exactly one character from `token`, a slice of `source`.
+fn parse_char(source: &str, token: &str) -> Result<char> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(Error::parse(source, token, "expected a single character")),
+    }
+}
+
+pub fn parse_rule(s: &str) -> Result<Rule> {
+    let (input, output) = s
+        .split_once("->")
+        .ok_or_else(|| Error::parse(s, s.trim(), "expected a rule like 'AB -> C'"))?;
 
     let input = input.trim();
     let output = output.trim();
 
     let mut in_chars = input.chars();
-    let first = in_chars.next().expect("Should be enough characters");
-    let second = in_chars.next().expect("Should be enough characters");
-    debug_assert!(in_chars.next().is_none());
+    let (first, second) = match (in_chars.next(), in_chars.next(), in_chars.next()) {
+        (Some(first), Some(second), None) => (first, second),
+        _ => return Err(Error::parse(s, input, "expected a pair of characters")),
+    };
 
-    let mut output_chars = output.chars();
-    let new_char = output_chars.next().expect("Should be enough chars");
-    debug_assert!(output_chars.next().is_none());
+    let new_char = parse_char(s, output)?;
 
-    ((first, second), new_char)
+    Ok(((first, second), new_char))
 }
 
-fn parse_rules(s: &str) -> RuleSet {
-    let iter = s.lines().map(parse_rule);
-
-    HashMap::from_iter(iter)
+pub fn parse_rules(s: &str) -> Result<RuleSet> {
+    s.lines()
+        .map(|line| parse_rule(line).map_err(|err| err.within(s, line)))
+        .collect()
 }
 
-pub fn part1() {
-    let mut input_buf =
-        BufReader::new(File::open("input/day14.txt").expect("Should have input file"));
-
-    let mut input = String::new();
-    input_buf
-        .read_to_string(&mut input)
-        .expect("Should read file fine");
-
-    let (initial_str, rules) = input.split_once("\n\n").expect("Should have an empty line");
-
-    let initial_state = parse_initial(initial_str.trim());
-
-    let rules = parse_rules(rules.trim());
+pub fn letter_spread(template: &str, rules: &RuleSet, steps: usize) -> usize {
+    let initial_state = parse_initial(template);
 
     let mut char_count = HashMap::new();
-    for c in initial_str.chars() {
+    for c in template.chars() {
         *char_count.entry(c).or_insert(0) += 1;
     }
 
-    let _ = (0..10).fold(initial_state, |state, _| {
-        run_rules(&state, &rules, &mut char_count)
+    let _ = (0..steps).fold(initial_state, |state, _| {
+        run_rules(&state, rules, &mut char_count)
     });
 
     let mut counts = char_count
@@ -100,44 +96,53 @@ pub fn part1() {
 
     counts.sort_unstable_by(|&(_, a), (_, b)| a.cmp(b));
 
-    let diff = counts.last().unwrap().1 - counts[0].1;
-
-    println!("Diff between smallest and biggest after 10 iter: {}", diff);
+    match (counts.first(), counts.last()) {
+        (Some(&(_, least)), Some(&(_, most))) => most - least,
+        _ => 0,
+    }
 }
-pub fn part2() {
-    let mut input_buf =
-        BufReader::new(File::open("input/day14.txt").expect("Should have input file"));
-
-    let mut input = String::new();
-    input_buf
-        .read_to_string(&mut input)
-        .expect("Should read file fine");
 
-    let (initial_str, rules) = input.split_once("\n\n").expect("Should have an empty line");
-
-    let initial_state = parse_initial(initial_str.trim());
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = (String, RuleSet);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (initial_str, rules) = input.split_once("\n\n").ok_or_else(|| {
+            Error::parse(
+                input,
+                &input[input.len()..],
+                "expected rules after the template",
+            )
+        })?;
+
+        let template = initial_str.trim();
+        if template.is_empty() || template.contains(char::is_whitespace) {
+            return Err(Error::parse(
+                input,
+                initial_str,
+                "expected a polymer template",
+            ));
+        }
 
-    let rules = parse_rules(rules.trim());
+        let rules = parse_rules(rules.trim()).map_err(|err| err.within(input, rules.trim()))?;
 
-    let mut char_count = HashMap::new();
-    for c in initial_str.chars() {
-        *char_count.entry(c).or_insert(0) += 1;
+        Ok((template.to_string(), rules))
     }
 
-    let _ = (0..40).fold(initial_state, |state, _| {
-        run_rules(&state, &rules, &mut char_count)
-    });
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let (template, rules) = input;
 
-    let mut counts = char_count
-        .iter()
-        .map(|(&c, &count)| (c, count))
-        .collect::<Vec<(char, usize)>>();
-
-    counts.sort_unstable_by(|&(_, a), (_, b)| a.cmp(b));
+        Ok(letter_spread(template, rules, 10).into())
+    }
 
-    let diff = counts.last().unwrap().1 - counts[0].1;
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let (template, rules) = input;
 
-    println!("Diff between smallest and biggest after 40 iter: {}", diff);
+        Ok(letter_spread(template, rules, 40).into())
+    }
 }
 
 #[cfg(test)]
@@ -148,7 +153,7 @@ mod test {
     #[test]
     fn parse_test() {
         let r = "CH -> B";
-        let rule = parse_rule(r);
+        let rule = parse_rule(r).unwrap();
 
         assert_eq!(rule, (('C', 'H'), 'B'));
     }
@@ -174,7 +179,7 @@ BC -> B
 CC -> N
 CN -> C";
 
-        let rules = parse_rules(rules);
+        let rules = parse_rules(rules).unwrap();
 
         let mut char_count = HashMap::from([('N', 2), ('C', 1), ('B', 1)]);
 
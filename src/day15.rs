@@ -1,25 +1,33 @@
 use std::{
     cmp::Reverse,
-    collections::{BinaryHeap, HashMap},
-    fs::File,
-    io::{BufReader, Read},
+    collections::{BinaryHeap, HashMap, HashSet},
 };
 
-struct Cave {
-    data: Vec<u8>,
-    width: usize,
+use crate::{
+    error::Result,
+    grid::{self, Grid},
+    render::{self, Frame, Render, Rgb},
+    solution::{Answer, Solution},
+};
+
+const LOW_RISK: Rgb = [200, 230, 200];
+const HIGH_RISK: Rgb = [20, 60, 30];
+const PATH: Rgb = [230, 30, 40];
+
+pub struct Cave {
+    pub risks: Grid<u8>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct PathEntry {
-    pos: (usize, usize),
-    cost: usize,
-    prev: Option<(usize, usize)>,
+pub struct PathEntry {
+    pub pos: (usize, usize),
+    pub cost: usize,
+    pub prev: Option<(usize, usize)>,
 }
 
 impl PartialOrd for PathEntry {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.cost.partial_cmp(&other.cost)
+        Some(self.cmp(other))
     }
 }
 
@@ -30,54 +38,43 @@ impl Ord for PathEntry {
 }
 
 impl Cave {
-    fn index(&self, (x, y): (usize, usize)) -> usize {
-        ((y * self.width) + x) as usize
-    }
-    fn coord(&self, index: usize) -> (usize, usize) {
-        (index % self.width, index / self.width)
-    }
-    fn neighbours(&self, coord: (usize, usize), tiled: bool) -> Vec<(usize, usize)> {
-        let (x, y) = coord;
-
-        let mut neighbours = Vec::new();
+    /// The dimensions of the cave, or of the 5x5 tiled cave.
+    pub fn dimensions(&self, tiled: bool) -> (usize, usize) {
+        let tiles = if tiled { 5 } else { 1 };
+        let (width, height) = self.risks.dimensions();
 
-        if x > 0 {
-            neighbours.push((x - 1, y));
-        }
-        if y > 0 {
-            neighbours.push((x, y - 1))
-        }
-        if x < (self.width - 1) || (tiled && x < (5 * self.width - 1)) {
-            neighbours.push((x + 1, y))
-        }
-        if y < (self.width - 1) || (tiled && y < (5 * self.width - 1)) {
-            neighbours.push((x, y + 1))
-        }
+        (tiles * width, tiles * height)
+    }
+    /// The bottom-right position of the cave, or of the 5x5 tiled cave.
+    pub fn goal(&self, tiled: bool) -> (usize, usize) {
+        let (width, height) = self.dimensions(tiled);
 
-        neighbours
+        (width - 1, height - 1)
+    }
+    pub fn neighbours(&self, coord: (usize, usize), tiled: bool) -> Vec<(usize, usize)> {
+        grid::neighbours4(coord, self.dimensions(tiled)).collect()
     }
 
-    fn heuristic_cost(&self, pos: (usize, usize), goal: (usize, usize)) -> usize {
+    pub fn heuristic_cost(&self, pos: (usize, usize), goal: (usize, usize)) -> usize {
         let (pos_x, pos_y) = pos;
         let (goal_x, goal_y) = goal;
 
         ((goal_x as i64 - pos_x as i64).abs() + (goal_y as i64 - pos_y as i64).abs()) as usize
     }
 
-    fn risk(&self, coord: (usize, usize), tiled: bool) -> usize {
+    pub fn risk(&self, coord: (usize, usize), tiled: bool) -> usize {
         if !tiled {
-            let index = self.index(coord);
-
-            self.data[index] as usize
+            self.risks[coord] as usize
         } else {
+            let (width, height) = self.risks.dimensions();
             let (x, y) = coord;
-            let add_x = x / self.width;
-            let x = x % self.width;
+            let add_x = x / width;
+            let x = x % width;
 
-            let add_y = y / self.width;
-            let y = y % self.width;
+            let add_y = y / height;
+            let y = y % height;
 
-            let risk = self.data[self.index((x, y))] as usize + add_x + add_y;
+            let risk = self.risks[(x, y)] as usize + add_x + add_y;
 
             if risk > 9 {
                 risk - 9
@@ -87,7 +84,12 @@ impl Cave {
         }
     }
 
-    fn find_path(&self, start: (usize, usize), end: (usize, usize), tiled: bool) -> Vec<PathEntry> {
+    pub fn find_path(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+        tiled: bool,
+    ) -> Vec<PathEntry> {
         let mut path = HashMap::from([(
             start,
             PathEntry {
@@ -163,65 +165,69 @@ impl Cave {
     }
 }
 
-fn parse_cave(s: &str, width: usize) -> Cave {
-    let mut data: Vec<u8> = Vec::new();
-    data.reserve(width * width);
-
-    s.lines().for_each(|line| {
-        line.trim()
-            .chars()
-            .map(|c| c.to_digit(10).expect("invalid digit") as u8)
-            .for_each(|x| data.push(x));
-    });
-
-    Cave { data, width }
+pub fn parse_cave(s: &str) -> Result<Cave> {
+    Ok(Cave {
+        risks: Grid::parse_digits(s)?,
+    })
 }
 
-fn cost(path: &[PathEntry]) -> usize {
+pub fn cost(path: &[PathEntry]) -> usize {
     path.iter().fold(0, |score, &entry| score + entry.cost) - path[0].cost
 }
 
-pub fn part1() {
-    let mut input_buf = BufReader::new(File::open("input/day15.txt").expect("Input should exist"));
+pub struct Day15;
 
-    let mut input = String::new();
-    input_buf.read_to_string(&mut input).expect("Read input");
+impl Solution for Day15 {
+    const DAY: u8 = 15;
 
-    let cave = parse_cave(&input, 100);
+    type Input = Cave;
 
-    let path = cave.find_path((0, 0), (99, 99), false);
-    let path_risk = cost(&path);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_cave(input)
+    }
 
-    println!("Risk of safest path: {}", path_risk);
-}
-pub fn part2() {
-    let mut input_buf = BufReader::new(File::open("input/day15.txt").expect("Input should exist"));
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let path = input.find_path((0, 0), input.goal(false), false);
 
-    let mut input = String::new();
-    input_buf.read_to_string(&mut input).expect("Read input");
+        Ok(cost(&path).into())
+    }
 
-    let cave = parse_cave(&input, 100);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let path = input.find_path((0, 0), input.goal(true), true);
 
-    let path = cave.find_path((0, 0), (499, 499), true);
-    let path_risk = cost(&path);
+        Ok(cost(&path).into())
+    }
+}
+
+/// The cave shaded from light for low risk to dark for high, with the safest
+/// path from the top left to the bottom right drawn over it.
+impl Render for Day15 {
+    fn frames(input: &Self::Input) -> Result<Vec<Frame>> {
+        let path = input
+            .find_path((0, 0), input.goal(false), false)
+            .into_iter()
+            .map(|entry| entry.pos)
+            .collect::<HashSet<(usize, usize)>>();
+
+        let frame = render::draw(&input.risks, |pos, &risk| {
+            if path.contains(&pos) || pos == (0, 0) {
+                PATH
+            } else {
+                render::blend(LOW_RISK, HIGH_RISK, (risk as f64 - 1.0) / 8.0)
+            }
+        });
 
-    println!("Risk of safest path: {}", path_risk);
+        Ok(vec![frame])
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store::fixture;
     #[test]
     fn example_1() {
-        let cave = Cave {
-            data: vec![
-                1, 1, 6, 3, 7, 5, 1, 7, 4, 2, 1, 3, 8, 1, 3, 7, 3, 6, 7, 2, 2, 1, 3, 6, 5, 1, 1, 3,
-                2, 8, 3, 6, 9, 4, 9, 3, 1, 5, 6, 9, 7, 4, 6, 3, 4, 1, 7, 1, 1, 1, 1, 3, 1, 9, 1, 2,
-                8, 1, 3, 7, 1, 3, 5, 9, 9, 1, 2, 4, 2, 1, 3, 1, 2, 5, 4, 2, 1, 6, 3, 9, 1, 2, 9, 3,
-                1, 3, 8, 5, 2, 1, 2, 3, 1, 1, 9, 4, 4, 5, 8, 1,
-            ],
-            width: 10,
-        };
+        let cave = parse_cave(&fixture(15, "example")).unwrap();
 
         let path = cave.find_path((0, 0), (9, 9), false);
 
@@ -230,15 +236,7 @@ mod tests {
 
     #[test]
     fn example_2() {
-        let cave = Cave {
-            data: vec![
-                1, 1, 6, 3, 7, 5, 1, 7, 4, 2, 1, 3, 8, 1, 3, 7, 3, 6, 7, 2, 2, 1, 3, 6, 5, 1, 1, 3,
-                2, 8, 3, 6, 9, 4, 9, 3, 1, 5, 6, 9, 7, 4, 6, 3, 4, 1, 7, 1, 1, 1, 1, 3, 1, 9, 1, 2,
-                8, 1, 3, 7, 1, 3, 5, 9, 9, 1, 2, 4, 2, 1, 3, 1, 2, 5, 4, 2, 1, 6, 3, 9, 1, 2, 9, 3,
-                1, 3, 8, 5, 2, 1, 2, 3, 1, 1, 9, 4, 4, 5, 8, 1,
-            ],
-            width: 10,
-        };
+        let cave = parse_cave(&fixture(15, "example")).unwrap();
 
         let neighbours = cave.neighbours((9, 0), true);
 
@@ -260,4 +258,16 @@ mod tests {
 
         assert_eq!(cost(&path), 315);
     }
+
+    #[test]
+    fn rectangular_cave() {
+        let cave = parse_cave("119\n991\n").unwrap();
+
+        assert_eq!(cave.goal(false), (2, 1));
+        assert_eq!(cave.goal(true), (14, 9));
+        assert_eq!(cost(&cave.find_path((0, 0), cave.goal(false), false)), 11);
+
+        assert!(parse_cave("119\n99\n").is_err());
+        assert!(parse_cave("").is_err());
+    }
 }
@@ -1,13 +1,21 @@
-use std::fs::read_to_string;
+use crate::{
+    error::{Error, Result},
+    solution::{Answer, Solution},
+};
+
+/// An error in the bit stream. `parse_hex` moves it to the hex digit it came from.
+fn stream_error(reason: &str) -> Error {
+    Error::parse("", "", reason)
+}
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-struct Header {
-    version: u8,
-    id: u8,
+pub struct Header {
+    pub version: u8,
+    pub id: u8,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-enum Operator {
+pub enum Operator {
     Sum,
     Product,
     Min,
@@ -17,91 +25,130 @@ enum Operator {
     EQ,
 }
 
+impl Operator {
+    /// The packet type id that marks this operator in a header.
+    pub fn type_id(self) -> u8 {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Min => 2,
+            Operator::Max => 3,
+            Operator::GT => 5,
+            Operator::LT => 6,
+            Operator::EQ => 7,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
-enum PacketType {
+pub enum PacketType {
     Literal(i64),
     Operator(Operator, Vec<Packet>),
 }
 #[derive(Debug, PartialEq, Clone)]
-struct Packet {
-    header: Header,
-    body: PacketType,
+pub struct Packet {
+    pub header: Header,
+    pub body: PacketType,
 }
 
-fn parse_number<I>(stream: &mut I, num_bits: usize) -> i64
+pub fn parse_number<I>(stream: &mut I, num_bits: usize) -> Result<i64>
 where
     I: Iterator<Item = char>,
 {
     let mut num_string = String::new();
 
     for _ in 0..num_bits {
-        num_string.push(stream.next().expect("Not enough bits in stream"));
+        num_string.push(
+            stream
+                .next()
+                .ok_or_else(|| stream_error("not enough bits in stream"))?,
+        );
     }
 
-    i64::from_str_radix(&num_string, 2).expect("input stream malformed")
+    i64::from_str_radix(&num_string, 2).map_err(|_| stream_error("expected only bits in stream"))
 }
 
-fn parse_literal<I>(stream: &mut I) -> (usize, i64)
+pub fn parse_literal<I>(stream: &mut I) -> Result<(usize, i64)>
 where
     I: Iterator<Item = char>,
 {
-    let mut literal = 0;
+    let mut literal: i64 = 0;
     let mut num_bits = 0;
 
     let mut keep_parsing = true;
     while keep_parsing {
-        keep_parsing = stream.next().expect("We should keep parsing????") == '1';
+        keep_parsing = stream
+            .next()
+            .ok_or_else(|| stream_error("literal ends early"))?
+            == '1';
         num_bits += 1;
 
+        if literal > (i64::MAX >> 4) {
+            return Err(stream_error("literal does not fit in 63 bits"));
+        }
+
         // Parse group ( 4 bits )
-        literal = (literal << 4) | parse_number(stream, 4);
+        literal = (literal << 4) | parse_number(stream, 4)?;
 
         num_bits += 4;
     }
 
-    (num_bits, literal)
+    Ok((num_bits, literal))
 }
 
-fn parse_header<I>(stream: &mut I) -> (usize, Header)
+pub fn parse_header<I>(stream: &mut I) -> Result<(usize, Header)>
 where
     I: Iterator<Item = char>,
 {
-    let version = parse_number(stream, 3) as u8;
-    let packet_type = parse_number(stream, 3) as u8;
+    let version = parse_number(stream, 3)? as u8;
+    let packet_type = parse_number(stream, 3)? as u8;
 
-    (
+    Ok((
         6,
         Header {
             version,
             id: packet_type,
         },
-    )
+    ))
 }
 
-enum LengthType {
+pub enum LengthType {
     NumBits(usize),
     NumPackets(usize),
 }
 
-fn parse_operator<I>(stream: &mut I, type_id: u8) -> (usize, PacketType)
+/// How deeply packets may nest. Real transmissions stay far below this, and it
+/// keeps hostile ones from overflowing the stack.
+pub const MAX_DEPTH: usize = 256;
+
+pub fn parse_operator<I>(stream: &mut I, type_id: u8) -> Result<(usize, PacketType)>
+where
+    I: Iterator<Item = char>,
+{
+    parse_operator_at(stream, type_id, 0)
+}
+
+fn parse_operator_at<I>(stream: &mut I, type_id: u8, depth: usize) -> Result<(usize, PacketType)>
 where
     I: Iterator<Item = char>,
 {
     let mut num_bits = 0;
 
-    let length_type = stream.next().expect("Packet body malformed");
+    let length_type = stream
+        .next()
+        .ok_or_else(|| stream_error("operator ends before its length type"))?;
     num_bits += 1;
 
     let length = match length_type {
         '0' => {
             num_bits += 15;
-            LengthType::NumBits(parse_number(stream, 15) as usize)
+            LengthType::NumBits(parse_number(stream, 15)? as usize)
         }
         '1' => {
             num_bits += 11;
-            LengthType::NumPackets(parse_number(stream, 11) as usize)
+            LengthType::NumPackets(parse_number(stream, 11)? as usize)
         }
-        _ => unreachable!(),
+        _ => return Err(stream_error("expected only bits in stream")),
     };
 
     let mut sub_packets = Vec::new();
@@ -110,15 +157,17 @@ where
         LengthType::NumBits(bits) => {
             let mut bits = bits;
             while bits > 0 {
-                let (parsed, sub) = parse_packet(stream);
+                let (parsed, sub) = parse_packet_at(stream, depth + 1)?;
                 sub_packets.push(sub);
                 num_bits += parsed;
-                bits -= parsed;
+                bits = bits
+                    .checked_sub(parsed)
+                    .ok_or_else(|| stream_error("sub-packets are longer than their operator"))?;
             }
         }
         LengthType::NumPackets(packets) => {
             for _ in 0..packets {
-                let (parsed, sub) = parse_packet(stream);
+                let (parsed, sub) = parse_packet_at(stream, depth + 1)?;
                 sub_packets.push(sub);
                 num_bits += parsed;
             }
@@ -133,38 +182,141 @@ where
         5 => Operator::GT,
         6 => Operator::LT,
         7 => Operator::EQ,
-        _ => unreachable!(),
+        _ => return Err(stream_error("expected an operator type")),
     };
 
-    (num_bits, PacketType::Operator(operator, sub_packets))
+    match operator {
+        Operator::GT | Operator::LT | Operator::EQ if sub_packets.len() != 2 => {
+            return Err(stream_error("comparison needs exactly two sub-packets"));
+        }
+        _ if sub_packets.is_empty() => {
+            return Err(stream_error("operator needs at least one sub-packet"));
+        }
+        _ => (),
+    }
+
+    Ok((num_bits, PacketType::Operator(operator, sub_packets)))
+}
+
+pub fn parse_packet<I>(stream: &mut I) -> Result<(usize, Packet)>
+where
+    I: Iterator<Item = char>,
+{
+    parse_packet_at(stream, 0)
 }
 
-fn parse_packet<I>(stream: &mut I) -> (usize, Packet)
+fn parse_packet_at<I>(stream: &mut I, depth: usize) -> Result<(usize, Packet)>
 where
     I: Iterator<Item = char>,
 {
-    let (header_bits, header) = parse_header(stream);
+    if depth > MAX_DEPTH {
+        return Err(stream_error("packets are nested too deeply"));
+    }
+
+    let (header_bits, header) = parse_header(stream)?;
     let (body_bits, body) = match header.id {
         4 => {
-            let (num_bits, literal) = parse_literal(stream);
+            let (num_bits, literal) = parse_literal(stream)?;
             (num_bits, PacketType::Literal(literal))
         }
-        _ => parse_operator(stream, header.id),
+        _ => parse_operator_at(stream, header.id, depth)?,
     };
 
-    (header_bits + body_bits, Packet { header, body })
+    Ok((header_bits + body_bits, Packet { header, body }))
 }
 
-fn hex_to_binary_string(s: &str) -> String {
+pub fn hex_to_binary_string(s: &str) -> Result<String> {
     let binary_strings = s
-        .chars()
-        .map(|c| u8::from_str_radix(&c.to_string(), 16).unwrap())
-        .map(|num| format!("{:04b}", num))
-        .collect::<Vec<String>>();
-    binary_strings.iter().map(|s| s.chars()).flatten().collect()
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(16)
+                .ok_or_else(|| Error::parse(s, &s[i..i + c.len_utf8()], "expected a hex digit"))
+        })
+        .map(|num| Ok(format!("{:04b}", num?)))
+        .collect::<Result<Vec<String>>>()?;
+    Ok(binary_strings.iter().flat_map(|s| s.chars()).collect())
+}
+
+/// Parses the outermost packet of a hex transmission.
+pub fn parse_hex(s: &str) -> Result<Packet> {
+    let binary = hex_to_binary_string(s)?;
+    let mut stream = binary.chars();
+
+    match parse_packet(&mut stream) {
+        Ok((_, packet)) => Ok(packet),
+        Err(err) => {
+            // Point at the hex digit holding the last bit read
+            let consumed = binary.len() - stream.as_str().len();
+            let digit = consumed.saturating_sub(1) / 4;
+
+            Err(err.at(s, &s[digit.min(s.len())..(digit + 1).min(s.len())]))
+        }
+    }
+}
+
+fn push_number(bits: &mut String, number: u64, num_bits: usize) {
+    bits.push_str(&format!("{:0width$b}", number, width = num_bits));
+}
+
+fn push_packet(bits: &mut String, packet: &Packet, by_count: bool) {
+    push_number(bits, packet.header.version.into(), 3);
+    push_number(bits, packet.header.id.into(), 3);
+
+    match &packet.body {
+        PacketType::Literal(literal) => {
+            let groups = (64 - literal.leading_zeros() as usize).max(1).div_ceil(4);
+            for group in (0..groups).rev() {
+                bits.push(if group > 0 { '1' } else { '0' });
+                push_number(bits, (*literal as u64 >> (group * 4)) & 0xf, 4);
+            }
+        }
+        PacketType::Operator(_, sub_packets) => {
+            let mut body = String::new();
+            for sub in sub_packets {
+                push_packet(&mut body, sub, by_count);
+            }
+
+            if by_count {
+                bits.push('1');
+                push_number(bits, sub_packets.len() as u64, 11);
+            } else {
+                bits.push('0');
+                push_number(bits, body.len() as u64, 15);
+            }
+            bits.push_str(&body);
+        }
+    }
+}
+
+/// Encodes `packet` as the bits [`parse_packet`] reads. Operators give their
+/// length as a count of sub-packets if `by_count`, otherwise as a number of bits.
+pub fn packet_to_binary(packet: &Packet, by_count: bool) -> String {
+    let mut bits = String::new();
+    push_packet(&mut bits, packet, by_count);
+    bits
+}
+
+/// Encodes `packet` as a hex transmission, padded with zeros to whole bytes.
+pub fn packet_to_hex(packet: &Packet, by_count: bool) -> String {
+    let mut bits = packet_to_binary(packet, by_count);
+    while !bits.len().is_multiple_of(8) {
+        bits.push('0');
+    }
+
+    bits.as_bytes()
+        .chunks(4)
+        .map(|digit| {
+            let value = digit.iter().enumerate().fold(0, |value, (i, &bit)| {
+                value | (((bit - b'0') as u32) << (3 - i))
+            });
+            std::char::from_digit(value, 16)
+                .unwrap_or('0')
+                .to_ascii_uppercase()
+        })
+        .collect()
 }
 
-fn sum_versions(packet: &Packet) -> usize {
+pub fn sum_versions(packet: &Packet) -> usize {
     match &packet.body {
         PacketType::Literal(_) => packet.header.version as usize,
         PacketType::Operator(_, sub_packets) => {
@@ -173,80 +325,69 @@ fn sum_versions(packet: &Packet) -> usize {
     }
 }
 
-fn eval_packet(p: &Packet) -> i64 {
+/// The value of a packet's expression, or an error if it doesn't fit in an `i64`.
+pub fn eval_packet(p: &Packet) -> Result<i64> {
     match &p.body {
-        PacketType::Literal(i) => *i,
+        PacketType::Literal(i) => Ok(*i),
         PacketType::Operator(op, sub_packets) => {
-            let mut it = sub_packets.iter();
-            let first = it.next().expect("At least 1 sub packet");
-            let first = eval_packet(first);
-            it.fold(first, |res, p| match op {
-                Operator::Sum => res + eval_packet(p),
-                Operator::Product => res * eval_packet(p),
-                Operator::Min => res.min(eval_packet(p)),
-                Operator::Max => res.max(eval_packet(p)),
-                Operator::GT => {
-                    if res > eval_packet(p) {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                Operator::LT => {
-                    if res < eval_packet(p) {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                Operator::EQ => {
-                    if res == eval_packet(p) {
-                        1
-                    } else {
-                        0
-                    }
-                }
+            let overflow = || Error::unsolvable("packet value overflows a 64-bit integer");
+
+            let mut values = sub_packets.iter().map(eval_packet);
+            let first = values
+                .next()
+                .ok_or_else(|| Error::unsolvable("operator has no sub-packets"))??;
+
+            values.try_fold(first, |res, value| {
+                let value = value?;
+                Ok(match op {
+                    Operator::Sum => res.checked_add(value).ok_or_else(overflow)?,
+                    Operator::Product => res.checked_mul(value).ok_or_else(overflow)?,
+                    Operator::Min => res.min(value),
+                    Operator::Max => res.max(value),
+                    Operator::GT => (res > value) as i64,
+                    Operator::LT => (res < value) as i64,
+                    Operator::EQ => (res == value) as i64,
+                })
             })
         }
     }
 }
 
-pub fn part1() {
-    let input = read_to_string("input/day16.txt").expect("Input file should exist");
-    let binary = hex_to_binary_string(&input);
-    let mut stream = binary.chars();
+pub struct Day16;
 
-    let (_, packet) = parse_packet(&mut stream);
+impl Solution for Day16 {
+    const DAY: u8 = 16;
 
-    let version_sum = sum_versions(&packet);
-    println!("Version sum: {}", version_sum);
-}
-pub fn part2() {
-    let input = read_to_string("input/day16.txt").expect("Input file should exist");
-    let binary = hex_to_binary_string(&input);
-    let mut stream = binary.chars();
+    type Input = Packet;
 
-    let (_, packet) = parse_packet(&mut stream);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_hex(input.trim()).map_err(|err| err.within(input, input.trim()))
+    }
 
-    let result = eval_packet(&packet);
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(sum_versions(input).into())
+    }
 
-    println!("Packet expresion is {}", result);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(eval_packet(input)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn convert_string() {
         let input = "D2FE28";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
 
         assert_eq!(binary, "110100101111111000101000");
 
         assert_eq!(
-            hex_to_binary_string("38006F45291200"),
+            hex_to_binary_string("38006F45291200").unwrap(),
             "00111000000000000110111101000101001010010001001000000000"
         );
     }
@@ -254,10 +395,10 @@ mod tests {
     #[test]
     fn parse_header_test() {
         let input = "D2FE28";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
 
-        let (header_bits, header) = parse_header(&mut binary);
+        let (header_bits, header) = parse_header(&mut binary).unwrap();
 
         assert_eq!(header, Header { version: 6, id: 4 });
         assert_eq!(header_bits, 6);
@@ -266,11 +407,11 @@ mod tests {
     #[test]
     fn parse_literal_test() {
         let input = "D2FE28";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
 
-        let _ = parse_header(&mut binary);
-        let (bits, number) = parse_literal(&mut binary);
+        let _ = parse_header(&mut binary).unwrap();
+        let (bits, number) = parse_literal(&mut binary).unwrap();
         assert_eq!(number, 2021);
         assert_eq!(bits, 15);
     }
@@ -278,14 +419,14 @@ mod tests {
     #[test]
     fn parse_operator_test() {
         let input = "38006F45291200";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
 
-        let (bits, header) = parse_header(&mut binary);
+        let (bits, header) = parse_header(&mut binary).unwrap();
         assert_eq!(bits, 6);
         assert_eq!(header.id, 6);
 
-        let (bits, operator) = parse_operator(&mut binary, header.id);
+        let (bits, operator) = parse_operator(&mut binary, header.id).unwrap();
         assert_eq!(bits, 43);
 
         if let PacketType::Operator(Operator::LT, packets) = operator {
@@ -293,18 +434,18 @@ mod tests {
             assert_eq!(packets[0].body, PacketType::Literal(10));
             assert_eq!(packets[1].body, PacketType::Literal(20));
         } else {
-            assert!(false);
+            unreachable!();
         }
 
         let input = "EE00D40C823060";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
 
-        let (bits, header) = parse_header(&mut binary);
+        let (bits, header) = parse_header(&mut binary).unwrap();
         assert_eq!(bits, 6);
         assert_eq!(header.id, 3);
 
-        let (bits, operator) = parse_operator(&mut binary, header.id);
+        let (bits, operator) = parse_operator(&mut binary, header.id).unwrap();
         assert_eq!(bits, 45);
 
         if let PacketType::Operator(Operator::Max, packets) = operator {
@@ -313,40 +454,40 @@ mod tests {
             assert_eq!(packets[1].body, PacketType::Literal(2));
             assert_eq!(packets[2].body, PacketType::Literal(3));
         } else {
-            assert!(false);
+            unreachable!();
         }
     }
 
     #[test]
     fn part1_examples() {
         let input = "8A004A801A8002F478";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
         let version_sum = sum_versions(&packet);
 
         assert_eq!(version_sum, 16);
 
         let input = "620080001611562C8802118E34";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
         let version_sum = sum_versions(&packet);
 
         assert_eq!(version_sum, 12);
 
         let input = "C0015000016115A2E0802F182340";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
         let version_sum = sum_versions(&packet);
 
         assert_eq!(version_sum, 23);
 
         let input = "A0016C880162017C3686B18A3D4780";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
         let version_sum = sum_versions(&packet);
 
         assert_eq!(version_sum, 31);
@@ -355,35 +496,141 @@ mod tests {
     #[test]
     fn part2_examples() {
         let input = "C200B40A82";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
-        let result = eval_packet(&packet);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
+        let result = eval_packet(&packet).unwrap();
 
         assert_eq!(result, 3);
 
         let input = "04005AC33890";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
-        let result = eval_packet(&packet);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
+        let result = eval_packet(&packet).unwrap();
 
         assert_eq!(result, 54);
 
         let input = "880086C3E88112";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
-        let result = eval_packet(&packet);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
+        let result = eval_packet(&packet).unwrap();
 
         assert_eq!(result, 7);
 
         let input = "9C0141080250320F1802104A08";
-        let binary = hex_to_binary_string(input);
+        let binary = hex_to_binary_string(input).unwrap();
         let mut binary = binary.chars();
-        let (_, packet) = parse_packet(&mut binary);
-        let result = eval_packet(&packet);
+        let (_, packet) = parse_packet(&mut binary).unwrap();
+        let result = eval_packet(&packet).unwrap();
 
         assert_eq!(result, 1);
     }
+
+    #[test]
+    fn parse_hex_errors() {
+        let err = parse_hex("D2FEZ28").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 0 line 1 column 5: expected a hex digit (found \"Z\")"
+        );
+
+        // Operator promising 27 bits of sub-packets, cut off after the first
+        let err = parse_hex("38006F4529").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 0 line 1 column 10: not enough bits in stream (found \"9\")"
+        );
+    }
+
+    fn literal(value: i64) -> Packet {
+        Packet {
+            header: Header { version: 0, id: 4 },
+            body: PacketType::Literal(value),
+        }
+    }
+
+    fn operator(operator: Operator, sub_packets: Vec<Packet>) -> Packet {
+        Packet {
+            header: Header {
+                version: 0,
+                id: operator.type_id(),
+            },
+            body: PacketType::Operator(operator, sub_packets),
+        }
+    }
+
+    #[test]
+    fn too_deep() {
+        let nest = |depth| (0..depth).fold(literal(1), |p, _| operator(Operator::Sum, vec![p]));
+
+        assert!(parse_hex(&packet_to_hex(&nest(MAX_DEPTH), true)).is_ok());
+
+        let err = parse_hex(&packet_to_hex(&nest(MAX_DEPTH + 1), true)).unwrap_err();
+        assert!(err.to_string().contains("nested too deeply"));
+    }
+
+    #[test]
+    fn eval_overflow() {
+        let big = operator(Operator::Product, vec![literal(i64::MAX), literal(2)]);
+        assert!(eval_packet(&big).is_err());
+
+        let fits = operator(Operator::Sum, vec![literal(i64::MAX - 1), literal(1)]);
+        assert_eq!(eval_packet(&fits).unwrap(), i64::MAX);
+    }
+
+    #[test]
+    fn packet_to_hex_test() {
+        for input in ["D2FE28", "38006F45291200", "EE00D40C823060"] {
+            let packet = parse_hex(input).unwrap();
+            let by_count = input.starts_with('E');
+
+            assert_eq!(packet_to_hex(&packet, by_count), input);
+        }
+    }
+
+    fn packet() -> impl Strategy<Value = Packet> {
+        let literal = (0..8_u8, 0..=i64::MAX).prop_map(|(version, literal)| Packet {
+            header: Header { version, id: 4 },
+            body: PacketType::Literal(literal),
+        });
+
+        literal.prop_recursive(4, 32, 4, |inner| {
+            let operator = prop_oneof![
+                Just(Operator::Sum),
+                Just(Operator::Product),
+                Just(Operator::Min),
+                Just(Operator::Max),
+                Just(Operator::GT),
+                Just(Operator::LT),
+                Just(Operator::EQ),
+            ];
+
+            (0..8_u8, operator, prop::collection::vec(inner, 1..4)).prop_map(
+                |(version, operator, mut sub_packets)| {
+                    if matches!(operator, Operator::GT | Operator::LT | Operator::EQ) {
+                        sub_packets.resize(2, sub_packets[0].clone());
+                    }
+
+                    Packet {
+                        header: Header {
+                            version,
+                            id: operator.type_id(),
+                        },
+                        body: PacketType::Operator(operator, sub_packets),
+                    }
+                },
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn hex_round_trip(packet in packet(), by_count in any::<bool>()) {
+            let hex = packet_to_hex(&packet, by_count);
+
+            prop_assert_eq!(parse_hex(&hex).unwrap(), packet);
+        }
+    }
 }
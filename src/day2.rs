@@ -1,151 +1,590 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use std::io::Write;
+
+use crate::{
+    error::{parse_token, Error, Result},
+    render::{self, Rgb},
+    solution::{Answer, Solution},
 };
 
-enum Instruction {
+const SURFACE: Rgb = [67, 99, 216];
+const COURSE: Rgb = [255, 215, 80];
+const END: Rgb = [230, 25, 75];
+const TEXT: Rgb = [200, 200, 200];
+
+/// The size of a depth profile in pixels, and the space left around the course.
+const PROFILE_SIZE: (f64, f64) = (800.0, 400.0);
+const PROFILE_MARGIN: f64 = 24.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Instruction {
     Forward(i32),
     Down(i32),
     Up(i32),
 }
 
 impl Instruction {
-    fn parse(s: &str) -> Option<Instruction> {
-        let mut parts = s.split(' ');
-        let inst = parts.next()?;
-        let magnitude = parts.next()?.parse::<i32>().ok()?;
+    /// Parses a command like `forward 5`. Errors point into `s`.
+    pub fn parse(s: &str) -> Result<Instruction> {
+        let mut parts = s.split_whitespace();
+        let verb = parts
+            .next()
+            .ok_or_else(|| Error::parse(s, s, "expected a command like 'forward 5'"))?;
 
-        match inst {
-            "forward" => Some(Instruction::Forward(magnitude)),
-            "down" => Some(Instruction::Down(magnitude)),
-            "up" => Some(Instruction::Up(magnitude)),
-            _ => None,
-        }
-    }
-}
+        let inst = match verb {
+            "forward" => Instruction::Forward,
+            "down" => Instruction::Down,
+            "up" => Instruction::Up,
+            _ => return Err(Error::parse(s, verb, "expected forward, down or up")),
+        };
 
-type State1 = (i32, i32);
+        let magnitude = parts.next().ok_or_else(|| {
+            Error::parse(
+                s,
+                &verb[verb.len()..],
+                "expected a distance after the command",
+            )
+        })?;
+        let magnitude = parse_token(s, magnitude, "a distance")?;
 
-fn execute_1(state: State1, inst: Instruction) -> State1 {
-    let (horiz, vert) = state;
-    match inst {
-        Instruction::Up(n) => (horiz, vert - n),
-        Instruction::Down(n) => (horiz, vert + n),
-        Instruction::Forward(n) => (horiz + n, vert),
+        match parts.next() {
+            Some(extra) => Err(Error::parse(
+                s,
+                extra,
+                "expected nothing after the distance",
+            )),
+            None => Ok(inst(magnitude)),
+        }
     }
 }
 
-fn execute_series<I>(instructions: I) -> State1
+/// Parses each line as an instruction, moving errors to the line they are on.
+pub fn parse_lines<I>(lines: I) -> impl Iterator<Item = Result<Instruction>>
 where
-    I: IntoIterator<Item = String>,
+    I: IntoIterator,
+    I::Item: AsRef<str>,
 {
-    let initial_state = (0, 0);
-    instructions
+    lines
         .into_iter()
-        .map(|line| Instruction::parse(line.trim()))
-        .flatten()
-        .fold(initial_state, execute_1)
+        .enumerate()
+        .map(|(i, line)| Instruction::parse(line.as_ref()).map_err(|err| err.on_line(i + 1)))
+}
+
+/// A way for the submarine to move, holding where it has got to.
+pub trait SubmarineModel {
+    /// Fails, leaving the model as it was, if the move would take it out of
+    /// range.
+    fn apply(&mut self, inst: Instruction) -> Result<()>;
+
+    /// The horizontal position and depth.
+    fn position(&self) -> (i64, i64);
+
+    /// Which way the submarine points, for models that keep track of it.
+    fn aim(&self) -> Option<i64> {
+        None
+    }
+}
+
+/// Up and down change the depth directly, as in part 1.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Plain {
+    pub horizontal: i64,
+    pub depth: i64,
 }
 
-pub fn part1() {
-    let input = BufReader::new(File::open("input/day2.txt").unwrap());
+fn out_of_range() -> Error {
+    Error::unsolvable("the course goes beyond a 64-bit integer")
+}
+
+impl SubmarineModel for Plain {
+    fn apply(&mut self, inst: Instruction) -> Result<()> {
+        let (value, change) = match inst {
+            Instruction::Up(n) => (&mut self.depth, -i64::from(n)),
+            Instruction::Down(n) => (&mut self.depth, i64::from(n)),
+            Instruction::Forward(n) => (&mut self.horizontal, i64::from(n)),
+        };
+        *value = value.checked_add(change).ok_or_else(out_of_range)?;
+
+        Ok(())
+    }
+
+    fn position(&self) -> (i64, i64) {
+        (self.horizontal, self.depth)
+    }
+}
+
+/// Up and down tilt the submarine, and going forward changes the depth by the
+/// aim, as in part 2.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Aimed {
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+impl SubmarineModel for Aimed {
+    fn apply(&mut self, inst: Instruction) -> Result<()> {
+        match inst {
+            Instruction::Up(n) => {
+                self.aim = self.aim.checked_sub(n.into()).ok_or_else(out_of_range)?
+            }
+            Instruction::Down(n) => {
+                self.aim = self.aim.checked_add(n.into()).ok_or_else(out_of_range)?
+            }
+            Instruction::Forward(n) => {
+                let n = i64::from(n);
+                let horizontal = self.horizontal.checked_add(n);
+                let depth = n
+                    .checked_mul(self.aim)
+                    .and_then(|dive| self.depth.checked_add(dive));
+                (self.horizontal, self.depth) = horizontal.zip(depth).ok_or_else(out_of_range)?;
+            }
+        }
 
-    let (h, v) = execute_series(input.lines().filter_map(|line| line.ok()));
+        Ok(())
+    }
 
-    let answer = h * v;
+    fn position(&self) -> (i64, i64) {
+        (self.horizontal, self.depth)
+    }
 
-    println!("Day2 part 1 answer is {:?}", answer);
+    fn aim(&self) -> Option<i64> {
+        Some(self.aim)
+    }
 }
 
-type State2 = (i32, i32, i32);
+/// The smallest of `values` and how far they spread, counting 0 so that the
+/// surface stays in view even if the course climbs out of the water.
+fn extent(values: impl Iterator<Item = i64>) -> (f64, f64) {
+    let (min, max) = values.fold((0, 0), |(min, max), value| (value.min(min), value.max(max)));
+
+    (min as f64, (max as f64 - min as f64).max(1.0))
+}
+
+/// A model that keeps every state of another one, from the one it started in to
+/// one after each instruction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trajectory<M> {
+    states: Vec<M>,
+}
+
+impl<M: SubmarineModel + Clone> Trajectory<M> {
+    pub fn new(start: M) -> Self {
+        Trajectory {
+            states: vec![start],
+        }
+    }
+
+    pub fn states(&self) -> &[M] {
+        &self.states
+    }
+
+    pub fn last(&self) -> &M {
+        self.states
+            .last()
+            .expect("a trajectory starts with a state")
+    }
+
+    /// Writes one row per state with its step, position, depth and aim, which is
+    /// left empty for models without one.
+    pub fn write_csv(&self, mut writer: impl Write) -> Result<()> {
+        writeln!(writer, "step,horizontal,depth,aim")?;
+
+        for (step, state) in self.states.iter().enumerate() {
+            let (horizontal, depth) = state.position();
+            let aim = state.aim().map_or_else(String::new, |aim| aim.to_string());
+            writeln!(writer, "{},{},{},{}", step, horizontal, depth, aim)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the depth against the horizontal position as an SVG image, with
+    /// the surface as a dashed line and the end of the course marked.
+    pub fn write_svg(&self, mut writer: impl Write) -> Result<()> {
+        let positions = self
+            .states
+            .iter()
+            .map(SubmarineModel::position)
+            .collect::<Vec<(i64, i64)>>();
+
+        let (min_x, span_x) = extent(positions.iter().map(|&(x, _)| x));
+        let (min_y, span_y) = extent(positions.iter().map(|&(_, y)| y));
+
+        let (width, height) = PROFILE_SIZE;
+        let point = |(x, y): (i64, i64)| {
+            (
+                PROFILE_MARGIN + (x as f64 - min_x) / span_x * (width - 2.0 * PROFILE_MARGIN),
+                PROFILE_MARGIN + (y as f64 - min_y) / span_y * (height - 2.0 * PROFILE_MARGIN),
+            )
+        };
+        let hex = |[r, g, b]: Rgb| format!("#{:02x}{:02x}{:02x}", r, g, b);
+
+        writeln!(
+            writer,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        )?;
+        writeln!(
+            writer,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            hex(render::BACKGROUND)
+        )?;
+
+        let (_, surface) = point((0, 0));
+        writeln!(
+            writer,
+            r#"<line x1="0" y1="{0:.1}" x2="{1}" y2="{0:.1}" stroke="{2}" stroke-dasharray="6 4"/>"#,
+            surface,
+            width,
+            hex(SURFACE)
+        )?;
+
+        let points = positions
+            .iter()
+            .map(|&position| {
+                let (x, y) = point(position);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect::<Vec<String>>();
+        writeln!(
+            writer,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+            points.join(" "),
+            hex(COURSE)
+        )?;
+
+        let end = self.last().position();
+        let (x, y) = point(end);
+        writeln!(
+            writer,
+            r#"<circle cx="{:.1}" cy="{:.1}" r="4" fill="{}"/>"#,
+            x,
+            y,
+            hex(END)
+        )?;
+
+        let deepest = positions.iter().map(|&(_, depth)| depth).max().unwrap_or(0);
+        writeln!(
+            writer,
+            r#"<text x="{}" y="{}" fill="{}" font-family="monospace" font-size="12">{} steps, ending at {} across and {} down, deepest {}</text>"#,
+            PROFILE_MARGIN,
+            height - PROFILE_MARGIN / 3.0,
+            hex(TEXT),
+            self.states.len() - 1,
+            end.0,
+            end.1,
+            deepest
+        )?;
+        writeln!(writer, "</svg>")?;
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<M: SubmarineModel + Clone> SubmarineModel for Trajectory<M> {
+    fn apply(&mut self, inst: Instruction) -> Result<()> {
+        let mut next = self.last().clone();
+        next.apply(inst)?;
+        self.states.push(next);
+
+        Ok(())
+    }
+
+    fn position(&self) -> (i64, i64) {
+        self.last().position()
+    }
 
-fn execute_2(state: State2, inst: Instruction) -> State2 {
-    let (h, v, aim) = state;
-    match inst {
-        Instruction::Up(n) => (h, v, aim - n),
-        Instruction::Down(n) => (h, v, aim + n),
-        Instruction::Forward(n) => (h + n, v + (n * aim), aim),
+    fn aim(&self) -> Option<i64> {
+        self.last().aim()
     }
 }
 
-fn execute_series_2<I>(instructions: I) -> State2
+/// Moves `model` by each of `instructions` in turn, stopping at the first move
+/// that takes it out of range.
+pub fn execute<M, I>(mut model: M, instructions: I) -> Result<M>
 where
-    I: IntoIterator<Item = String>,
+    M: SubmarineModel,
+    I: IntoIterator<Item = Instruction>,
 {
-    let initial_state = (0, 0, 0);
     instructions
         .into_iter()
-        .map(|line| Instruction::parse(line.trim()))
-        .flatten()
-        .fold(initial_state, execute_2)
+        .try_for_each(|inst| model.apply(inst))?;
+
+    Ok(model)
+}
+
+/// Moves `model` by each line of `lines` that is an instruction, and returns
+/// why each of the others was skipped, in line order. Parsing with
+/// [`Day2::parse`] is the strict way, stopping at the first bad line.
+pub fn execute_lenient<M, I>(mut model: M, lines: I) -> Result<(M, Vec<Error>)>
+where
+    M: SubmarineModel,
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut warnings = Vec::new();
+    for inst in parse_lines(lines) {
+        match inst {
+            Ok(inst) => model.apply(inst)?,
+            Err(err) => warnings.push(err),
+        }
+    }
+
+    Ok((model, warnings))
+}
+
+/// The horizontal position times the depth the model ends at.
+fn final_product(model: impl SubmarineModel) -> Result<Answer> {
+    let (horizontal, depth) = model.position();
+
+    horizontal
+        .checked_mul(depth)
+        .map(Answer::from)
+        .ok_or_else(out_of_range)
 }
 
-pub fn part2() {
-    let input = BufReader::new(File::open("input/day2.txt").unwrap());
+pub struct Day2;
 
-    let (h, v, _) = execute_series_2(input.lines().filter_map(|line| line.ok()));
+impl Solution for Day2 {
+    const DAY: u8 = 2;
 
-    let answer = h * v;
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input.lines()).collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        final_product(execute(Plain::default(), input.iter().copied())?)
+    }
 
-    println!("Day2 part 2 answer is {:?}", answer);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        final_product(execute(Aimed::default(), input.iter().copied())?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::position;
+
+    const EXAMPLE: [&str; 6] = [
+        "forward 5",
+        "down 5   ",
+        "forward 8",
+        "up 3",
+        "down 8   ",
+        "forward 2",
+    ];
+
+    fn example() -> Vec<Instruction> {
+        Day2::parse(&EXAMPLE.join("\n")).unwrap()
+    }
+
+    fn moved<M: SubmarineModel + Default>(inst: Instruction) -> M {
+        let mut model = M::default();
+        model.apply(inst).unwrap();
+        model
+    }
 
     #[test]
     fn foward() {
-        let state = (0, 0);
-        let inst = Instruction::Forward(12);
+        let plain = moved::<Plain>(Instruction::Forward(12));
+        assert_eq!(plain.position(), (12, 0));
 
-        assert_eq!(execute_1(state, inst), (12, 0));
+        let aimed = Aimed {
+            aim: 2,
+            ..Aimed::default()
+        };
+        assert_eq!(
+            execute(aimed, [Instruction::Forward(3)]).unwrap(),
+            Aimed {
+                horizontal: 3,
+                depth: 6,
+                aim: 2
+            }
+        );
     }
 
     #[test]
     fn up() {
-        let state = (0, 0);
-        let inst = Instruction::Up(12);
+        let plain = moved::<Plain>(Instruction::Up(12));
+        assert_eq!(plain.position(), (0, -12));
 
-        assert_eq!(execute_1(state, inst), (0, -12));
+        let aimed = moved::<Aimed>(Instruction::Up(12));
+        assert_eq!((aimed.position(), aimed.aim), ((0, 0), -12));
     }
+
     #[test]
     fn down() {
-        let state = (0, 0);
-        let inst = Instruction::Down(12);
+        let plain = moved::<Plain>(Instruction::Down(12));
+        assert_eq!(plain.position(), (0, 12));
 
-        assert_eq!(execute_1(state, inst), (0, 12));
+        let aimed = moved::<Aimed>(Instruction::Down(12));
+        assert_eq!((aimed.position(), aimed.aim), ((0, 0), 12));
     }
 
     #[test]
     fn part1() {
-        let input = vec![
-            "forward 5",
-            "down 5   ",
-            "forward 8",
-            "up 3",
-            "down 8   ",
-            "forward 2",
-        ];
-
-        let (h, v) = execute_series(input.into_iter().map(|s| String::from(s)));
-        assert_eq!(h, 15);
-        assert_eq!(v, 10);
+        let plain = execute(Plain::default(), example()).unwrap();
+        assert_eq!(plain.horizontal, 15);
+        assert_eq!(plain.depth, 10);
     }
 
     #[test]
     fn part2() {
-        let input = vec![
-            "forward 5",
-            "down 5   ",
-            "forward 8",
-            "up 3",
-            "down 8   ",
-            "forward 2",
-        ];
-
-        let (h, v, _) = execute_series_2(input.into_iter().map(|s| String::from(s)));
-        assert_eq!(h, 15);
-        assert_eq!(v, 60);
+        let aimed = execute(Aimed::default(), example()).unwrap();
+        assert_eq!(aimed.horizontal, 15);
+        assert_eq!(aimed.depth, 60);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Instruction::parse("  down 7 ").unwrap(),
+            Instruction::Down(7)
+        );
+
+        let err = Instruction::parse("forwrad 5").unwrap_err();
+        assert_eq!(position(&err), (1, 1, "forwrad"));
+        assert!(err.to_string().contains("expected forward, down or up"));
+
+        let err = Instruction::parse("up").unwrap_err();
+        assert_eq!(position(&err), (1, 3, ""));
+        assert!(err.to_string().contains("expected a distance"));
+
+        let err = Instruction::parse("down five").unwrap_err();
+        assert_eq!(position(&err), (1, 6, "five"));
+        assert!(err.to_string().contains("expected a distance"));
+
+        assert_eq!(
+            position(&Instruction::parse("up 1 2").unwrap_err()),
+            (1, 6, "2")
+        );
+        assert_eq!(position(&Instruction::parse("").unwrap_err()), (1, 1, ""));
+    }
+
+    #[test]
+    fn strict_and_lenient() {
+        let lines = ["forward 5", "forwrad 3", "down 2", "up", "forward 1"];
+
+        let err = Day2::parse(&lines.join("\n")).unwrap_err();
+        assert_eq!(position(&err), (2, 1, "forwrad"));
+
+        let (plain, warnings) = execute_lenient(Plain::default(), lines).unwrap();
+        assert_eq!(plain.position(), (6, 2));
+        let skipped = warnings.iter().map(position).collect::<Vec<_>>();
+        assert_eq!(skipped, [(2, 1, "forwrad"), (4, 3, "")]);
+
+        let (aimed, warnings) = execute_lenient(Aimed::default(), EXAMPLE).unwrap();
+        assert_eq!(aimed.position(), (15, 60));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn trajectory() {
+        let trajectory = execute(Trajectory::new(Aimed::default()), example()).unwrap();
+        assert_eq!(trajectory.states().len(), 7);
+        assert_eq!(trajectory.position(), (15, 60));
+        assert_eq!(trajectory.aim(), Some(10));
+        assert_eq!(
+            trajectory.states()[2],
+            Aimed {
+                horizontal: 5,
+                depth: 0,
+                aim: 5
+            }
+        );
+
+        let mut csv = Vec::new();
+        trajectory.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("step,horizontal,depth,aim\n0,0,0,0\n1,5,0,0\n2,5,0,5\n"));
+        assert!(csv.ends_with("6,15,60,10\n"));
+
+        // Skipped lines leave no state behind
+        let (plain, _) =
+            execute_lenient(Trajectory::new(Plain::default()), ["up 2", "x", "up"]).unwrap();
+        let mut csv = Vec::new();
+        plain.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "step,horizontal,depth,aim\n0,0,0,\n1,0,-2,\n"
+        );
+    }
+
+    #[test]
+    fn trajectory_svg() {
+        let trajectory = execute(Trajectory::new(Aimed::default()), example()).unwrap();
+        let mut svg = Vec::new();
+        trajectory.write_svg(&mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        // The course starts at the surface in the top left and ends at the bottom right
+        assert!(svg.contains("points=\"24.0,24.0 "));
+        assert!(svg.contains("<circle cx=\"776.0\" cy=\"376.0\""));
+        assert!(svg.contains("6 steps, ending at 15 across and 60 down, deepest 60"));
+
+        // A course that goes nowhere still draws
+        let mut svg = Vec::new();
+        Trajectory::new(Plain::default())
+            .write_svg(&mut svg)
+            .unwrap();
+        assert!(String::from_utf8(svg)
+            .unwrap()
+            .contains("points=\"24.0,24.0\""));
+    }
+
+    /// A model the parser knows nothing about: how far the submarine travels.
+    #[derive(Default)]
+    struct Odometer(i64);
+
+    impl SubmarineModel for Odometer {
+        fn apply(&mut self, inst: Instruction) -> Result<()> {
+            match inst {
+                Instruction::Forward(n) | Instruction::Down(n) | Instruction::Up(n) => {
+                    self.0 += i64::from(n)
+                }
+            }
+
+            Ok(())
+        }
+
+        fn position(&self) -> (i64, i64) {
+            (self.0, 0)
+        }
+    }
+
+    #[test]
+    fn other_models() {
+        assert_eq!(execute(Odometer::default(), example()).unwrap().0, 31);
+    }
+
+    #[test]
+    fn out_of_range() {
+        // Past what fits in 32 bits, which the real answer nearly fills
+        let input = Day2::parse("forward 100000\ndown 100000\nforward 100000").unwrap();
+        assert_eq!(Day2::part1(&input).unwrap(), Answer::Number(20_000_000_000));
+        assert_eq!(
+            Day2::part2(&input).unwrap(),
+            Answer::Number(2_000_000_000_000_000)
+        );
+
+        let max = i32::MAX;
+        let lines = format!("down {0}\nforward {0}\nforward {0}\nforward {0}", max);
+        let input = Day2::parse(&lines).unwrap();
+        for part in [Day2::part1, Day2::part2] {
+            assert!(matches!(part(&input), Err(Error::Unsolvable { .. })));
+        }
+
+        let mut aimed = execute(Aimed::default(), input[..3].iter().copied()).unwrap();
+        let before = aimed;
+        let err = aimed.apply(Instruction::Forward(max)).unwrap_err();
+        assert!(err.to_string().contains("beyond a 64-bit integer"));
+        assert_eq!(aimed, before);
     }
 }
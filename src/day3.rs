@@ -1,23 +1,29 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use std::collections::BTreeMap;
+
+use crate::{
+    error::{Error, Result},
+    solution::{Answer, Solution, Streaming},
 };
 
-fn string_to_bits(s: &str) -> Vec<u32> {
+pub fn string_to_bits(s: &str) -> Result<Vec<u32>> {
     s.trim()
-        .chars()
-        .map(|char| char.to_digit(10).unwrap())
+        .char_indices()
+        .map(|(i, char)| {
+            char.to_digit(2).ok_or_else(|| {
+                let token = &s.trim()[i..i + char.len_utf8()];
+                Error::parse(s, token, "expected a binary digit")
+            })
+        })
         .collect()
 }
 
-fn vec_to_num(digits: &[u32]) -> u32 {
+pub fn vec_to_num(digits: &[u32]) -> u32 {
     digits.iter().fold(0, |num, digit| (num << 1) | (digit & 1))
 }
 
-fn count_bits(mut numbers: Vec<&str>) -> Vec<u32> {
-    let mut counts = string_to_bits(numbers.pop().unwrap());
-    numbers.into_iter().for_each(|s| {
-        let new_bits = string_to_bits(s);
+pub fn count_bits(numbers: &[Vec<u32>]) -> Vec<u32> {
+    let mut counts = vec![0; numbers.first().map_or(0, |bits| bits.len())];
+    numbers.iter().for_each(|new_bits| {
         new_bits.iter().enumerate().for_each(|(i, bit)| {
             counts[i] += bit;
         });
@@ -26,41 +32,11 @@ fn count_bits(mut numbers: Vec<&str>) -> Vec<u32> {
     counts
 }
 
-pub fn part1() {
-    let input = BufReader::new(File::open("input/day3.txt").unwrap());
-
-    let strings = input.lines().map(|s| s.unwrap()).collect::<Vec<String>>();
-    let borrowed = strings.iter().map(|s| s as &str).collect();
-
-    let counts = count_bits(borrowed);
-
-    let final_num = vec_to_num(
-        counts
-            .iter()
-            .map(|total| {
-                if *total > (strings.len() as u32 / 2) {
-                    1
-                } else {
-                    0
-                }
-            })
-            .collect::<Vec<u32>>()
-            .as_slice(),
-    );
-
-    let gamma = final_num;
-    let epsilon = !gamma;
-    let epsilon = epsilon & ((2_u32).pow(12) - 1);
-
-    println!("Power consumption is {}", gamma * epsilon);
-}
-
-fn part2_log(numbers: Vec<u32>, max_bit: u32) -> u32 {
-    let (mut big, mut small): (Vec<u32>, Vec<u32>) = numbers
-        .iter()
-        .partition(|num| (**num & ((1 << (max_bit + 1)) - 1)) >= (1 << max_bit));
+pub fn part2_log(numbers: Vec<u32>, max_bit: u32) -> Option<u64> {
+    let mut big = numbers.clone();
+    let mut small = numbers;
 
-    for bit in (0..(max_bit)).rev() {
+    for bit in (0..=max_bit).rev() {
         if big.len() > 1 {
             let (big1, big2): (Vec<u32>, Vec<u32>) = big
                 .iter()
@@ -74,7 +50,8 @@ fn part2_log(numbers: Vec<u32>, max_bit: u32) -> u32 {
                 .iter()
                 .partition(|num| (**num & ((1 << (bit + 1)) - 1)) >= (1 << bit));
 
-            small = if small1.len() >= small2.len() {
+            // Least common, unless every number has the same bit
+            small = if small1.is_empty() || (!small2.is_empty() && small1.len() >= small2.len()) {
                 small2
             } else {
                 small1
@@ -86,33 +63,195 @@ fn part2_log(numbers: Vec<u32>, max_bit: u32) -> u32 {
         }
     }
 
-    big[0] * small[0]
+    Some(*big.first()? as u64 * *small.first()? as u64)
 }
 
-pub fn part2() {
-    let input = BufReader::new(File::open("input/day3.txt").unwrap());
+/// The rating left after filtering numbers bit by bit from the top, keeping the
+/// most common bit (ties keep 1s) or the least common (ties keep 0s). `counts`
+/// holds how often each `width` bit number appears.
+pub fn rating(counts: &BTreeMap<u32, u64>, width: u32, most_common: bool) -> Option<u32> {
+    let total = |from: u32, to: u32| counts.range(from..to).map(|(_, &n)| n).sum::<u64>();
 
-    let numbers = input
-        .lines()
-        .map(|s| u32::from_str_radix(s.unwrap().trim(), 2).unwrap())
-        .collect::<Vec<u32>>();
+    // Numbers sharing the bits chosen so far are the range low..low + 2 * half
+    let mut low = 0;
+    for bit in (0..width).rev() {
+        let half = 1 << bit;
+        let zeros = total(low, low + half);
+        let ones = total(low + half, low + 2 * half);
 
-    let answer = part2_log(numbers, 11);
+        let keep_ones = if most_common {
+            ones >= zeros
+        } else {
+            ones > 0 && (zeros == 0 || ones < zeros)
+        };
+        if keep_ones {
+            low += half;
+        }
+    }
 
-    println!("Part2: {}", answer);
+    counts.contains_key(&low).then_some(low)
+}
+
+/// What streaming keeps of the report: how many numbers have each bit set, and
+/// how often each number appears. That is an entry per distinct number, fewer
+/// than the lines for narrow reports with repeats, but as many as the lines for
+/// wide ones, where up to 2^31 numbers are possible.
+#[derive(Debug, Default)]
+pub struct Report {
+    width: usize,
+    lines: u64,
+    ones: Vec<u64>,
+    counts: BTreeMap<u32, u64>,
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Vec<u32>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut width = None;
+
+        input
+            .lines()
+            .map(|line| {
+                let bits = string_to_bits(line).map_err(|err| err.within(input, line))?;
+
+                let expected = *width.get_or_insert(bits.len());
+                if bits.len() != expected || !(1..=31).contains(&bits.len()) {
+                    let reason = format!("expected {} bits, between 1 and 31", expected);
+                    return Err(Error::parse(input, line.trim(), reason));
+                }
+
+                Ok(bits)
+            })
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        if input.is_empty() {
+            return Err(Error::unsolvable("no numbers in input"));
+        }
+
+        let counts = count_bits(input);
+
+        let final_num = vec_to_num(
+            counts
+                .iter()
+                .map(|total| {
+                    if *total > (input.len() as u32 / 2) {
+                        1
+                    } else {
+                        0
+                    }
+                })
+                .collect::<Vec<u32>>()
+                .as_slice(),
+        );
+
+        let gamma = final_num;
+        let epsilon = !gamma;
+        let epsilon = epsilon & ((2_u32).pow(counts.len() as u32) - 1);
+
+        Ok((gamma as u64 * epsilon as u64).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let numbers = input
+            .iter()
+            .map(|bits| vec_to_num(bits))
+            .collect::<Vec<u32>>();
+
+        let max_bit = input.first().map_or(0, |bits| bits.len() as u32 - 1);
+
+        part2_log(numbers, max_bit)
+            .map(Answer::from)
+            .ok_or_else(|| Error::unsolvable("no rating left after filtering"))
+    }
+}
+
+impl Streaming for Day3 {
+    type Summary = Report;
+
+    fn add_line(summary: &mut Self::Summary, line: &str) -> Result<()> {
+        let bits = string_to_bits(line)?;
+
+        if summary.lines == 0 {
+            summary.width = bits.len();
+            summary.ones = vec![0; bits.len()];
+        }
+        if bits.len() != summary.width || !(1..=31).contains(&bits.len()) {
+            let reason = format!("expected {} bits, between 1 and 31", summary.width);
+            return Err(Error::parse(line, line.trim(), reason));
+        }
+
+        summary.lines += 1;
+        for (ones, bit) in summary.ones.iter_mut().zip(&bits) {
+            *ones += *bit as u64;
+        }
+        *summary.counts.entry(vec_to_num(&bits)).or_insert(0) += 1;
+
+        Ok(())
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Result<Answer> {
+        if summary.lines == 0 {
+            return Err(Error::unsolvable("no numbers in input"));
+        }
+
+        let gamma = summary.ones.iter().fold(0_u64, |gamma, &ones| {
+            (gamma << 1) | (ones > summary.lines / 2) as u64
+        });
+        let epsilon = !gamma & ((1 << summary.width) - 1);
+
+        Ok((gamma * epsilon).into())
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Result<Answer> {
+        let width = summary.width as u32;
+
+        rating(&summary.counts, width, true)
+            .zip(rating(&summary.counts, width, false))
+            .map(|(oxygen, co2)| Answer::from(oxygen as u64 * co2 as u64))
+            .ok_or_else(|| Error::unsolvable("no rating left after filtering"))
+    }
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::{generate, solution, store::fixture};
+
+    #[test]
+    fn stream_matches_parse() {
+        let inputs = [
+            fixture(3, "example"),
+            generate::generate(3, 300, 3).unwrap(),
+            "101\n101\n011\n000\n".to_string(),
+        ];
+
+        for input in inputs {
+            let streamed = solution::solve_stream::<Day3>(&mut input.as_bytes(), None).unwrap();
+
+            assert_eq!(
+                streamed.answers,
+                solution::solve::<Day3>(&input, None).unwrap()
+            );
+        }
+
+        let err = solution::solve_stream::<Day3>(&mut "101\n11\n".as_bytes(), None).unwrap_err();
+        assert!(matches!(err, Error::Parse { line: 2, .. }));
+    }
 
     #[test]
     fn string_to_bits_test() {
         let num = 0b110110;
         let string = "110110";
 
-        assert_eq!(vec_to_num(&string_to_bits(string)), num);
+        assert_eq!(vec_to_num(&string_to_bits(string).unwrap()), num);
     }
 
     #[test]
@@ -122,6 +261,14 @@ mod test {
             0b11001, 0b00010, 0b01010,
         ];
 
-        assert_eq!(part2_log(numbers, 4), 230);
+        assert_eq!(part2_log(numbers, 4), Some(230));
+
+        // Mostly zeros in the top bit
+        let numbers = vec![0b000, 0b010, 0b011, 0b100];
+        assert_eq!(part2_log(numbers, 2), Some(0b011 * 0b100));
+
+        // The CO2 filter keeps both 01x when they share the middle bit
+        let numbers = vec![0b010, 0b011, 0b100, 0b101, 0b110];
+        assert_eq!(part2_log(numbers, 2), Some(0b101 * 0b010));
     }
 }
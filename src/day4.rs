@@ -1,10 +1,10 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader, Read},
+use crate::{
+    error::{parse_token, Error, Result},
+    solution::{Answer, Solution},
 };
 
-#[derive(Debug)]
-struct Board {
+#[derive(Debug, Clone)]
+pub struct Board {
     pub numbers: Vec<i32>,
     pub size: i32,
 }
@@ -51,91 +51,109 @@ impl Board {
     }
 }
 
-fn parse_board(s: &str) -> Board {
+/// Bingo numbers are non-negative, since -1 marks a called number.
+fn parse_bingo_number(source: &str, token: &str) -> Result<i32> {
+    let num = parse_token::<i32>(source, token, "a number")?;
+    if num < 0 {
+        return Err(Error::parse(
+            source,
+            token,
+            "expected a non-negative number",
+        ));
+    }
+
+    Ok(num)
+}
+
+pub fn parse_board(s: &str) -> Result<Board> {
     let numbers = s
         .split_whitespace()
-        .map(|num| num.parse::<i32>().unwrap())
-        .collect::<Vec<i32>>();
+        .map(|num| parse_bingo_number(s, num))
+        .collect::<Result<Vec<i32>>>()?;
 
     let size = (numbers.len() as f64).sqrt() as i32;
+    if size == 0 || (size * size) as usize != numbers.len() {
+        return Err(Error::parse(s, s, "expected a square board of numbers"));
+    }
 
-    Board { numbers, size }
+    Ok(Board { numbers, size })
 }
 
-fn parse_all_boards(s: &str) -> Vec<Board> {
-    s.trim().split("\n\n").map(parse_board).collect()
+pub fn parse_all_boards(s: &str) -> Result<Vec<Board>> {
+    s.trim()
+        .split("\n\n")
+        .map(|board| parse_board(board).map_err(|err| err.within(s, board)))
+        .collect()
 }
 
-pub fn part1() {
-    let mut input = BufReader::new(File::open("input/day4.txt").unwrap());
+pub fn parse_draws(s: &str) -> Result<Vec<i32>> {
+    s.split(',')
+        .map(|draw| parse_bingo_number(s, draw.trim()))
+        .collect()
+}
 
-    let mut draws = String::new();
-    input
-        .read_line(&mut draws)
-        .expect("Reading first line as draws");
+pub struct Bingo {
+    pub draws: Vec<i32>,
+    pub boards: Vec<Board>,
+}
 
-    let draws = draws
-        .split(',')
-        .filter_map(|s| s.parse::<i32>().ok())
-        .collect::<Vec<i32>>();
+pub struct Day4;
 
-    let mut boards = String::new();
-    input
-        .read_to_string(&mut boards)
-        .expect("Reading rest of input into boards");
+impl Solution for Day4 {
+    const DAY: u8 = 4;
 
-    let mut boards = parse_all_boards(&boards);
+    type Input = Bingo;
 
-    for draw in draws {
-        for board in &mut boards {
-            board.mark_num(draw);
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (draws, boards) = input
+            .split_once('\n')
+            .ok_or_else(|| Error::parse(input, input, "expected draws followed by boards"))?;
 
-            if board.is_won() {
-                println!("Winning score: {}", board.value() * draw);
-                return;
-            }
-        }
+        Ok(Bingo {
+            draws: parse_draws(draws).map_err(|err| err.within(input, draws))?,
+            boards: parse_all_boards(boards).map_err(|err| err.within(input, boards))?,
+        })
     }
-}
 
-pub fn part2() {
-    let mut input = BufReader::new(File::open("input/day4.txt").unwrap());
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let mut boards = input.boards.clone();
 
-    let mut draws = String::new();
-    input
-        .read_line(&mut draws)
-        .expect("Reading first line as draws");
+        for &draw in &input.draws {
+            for board in &mut boards {
+                board.mark_num(draw);
 
-    let draws = draws
-        .split(',')
-        .filter_map(|s| s.parse::<i32>().ok())
-        .collect::<Vec<i32>>();
+                if board.is_won() {
+                    return Ok((board.value() as i64 * draw as i64).into());
+                }
+            }
+        }
 
-    let mut boards = String::new();
-    input
-        .read_to_string(&mut boards)
-        .expect("Reading rest of input into boards");
+        Err(Error::unsolvable("no board wins"))
+    }
 
-    let mut boards = parse_all_boards(&boards);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let mut boards = input.boards.clone();
 
-    let num_boards = boards.len();
-    let mut num_winners = 0;
+        let num_boards = boards.len();
+        let mut num_winners = 0;
 
-    for draw in draws {
-        for board in &mut boards {
-            if !board.is_won() {
-                board.mark_num(draw);
+        for &draw in &input.draws {
+            for board in &mut boards {
+                if !board.is_won() {
+                    board.mark_num(draw);
 
-                if board.is_won() {
-                    num_winners += 1;
+                    if board.is_won() {
+                        num_winners += 1;
 
-                    if num_winners == num_boards {
-                        println!("Winning score: {}", board.value() * draw);
-                        return;
+                        if num_winners == num_boards {
+                            return Ok((board.value() as i64 * draw as i64).into());
+                        }
                     }
                 }
             }
         }
+
+        Err(Error::unsolvable("not every board wins"))
     }
 }
 
@@ -143,6 +161,23 @@ pub fn part2() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_errors() {
+        let err = Day4::parse("1,2,x\n\n1 2\n3 4").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 0 line 1 column 5: expected a number (found \"x\")"
+        );
+
+        let err = Day4::parse("1,2\n\n1 2\n3 -4").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 0 line 4 column 3: expected a non-negative number (found \"-4\")"
+        );
+
+        assert!(Day4::parse("1,2\n\n1 2 3\n4 5").is_err());
+    }
+
     #[test]
     fn check_board() {
         let board = Board {
@@ -1,36 +1,53 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader},
+use std::{collections::HashMap, convert::TryFrom, fmt};
+
+use crate::{
+    error::{parse_token, Error, Result},
+    solution::{Answer, Solution, Streaming},
 };
 
 #[derive(PartialEq, Debug)]
-struct Line {
+pub struct Line {
     pub start: (i32, i32),
     pub end: (i32, i32),
 }
 
-impl From<&str> for Line {
-    fn from(s: &str) -> Self {
-        let mut coords = [(0, 0); 2];
-        s.split("->")
-            .map(|s| s.trim())
-            .map(|s| s.split(','))
-            .map(|mut nums| {
-                (
-                    nums.next().unwrap().parse::<i32>().unwrap(),
-                    nums.next().unwrap().parse::<i32>().unwrap(),
-                )
-            })
-            .enumerate()
-            .for_each(|(i, coord)| coords[i] = coord);
-
-        let [start, end] = coords;
-        Self { start, end }
+fn parse_coord(line: &str, s: &str) -> Result<(i32, i32)> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| Error::parse(line, s, "expected a coordinate like 'x,y'"))?;
+
+    Ok((
+        parse_token(line, x.trim(), "a number")?,
+        parse_token(line, y.trim(), "a number")?,
+    ))
+}
+
+impl TryFrom<&str> for Line {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        let (start, end) = s
+            .split_once("->")
+            .ok_or_else(|| Error::parse(s, s.trim(), "expected a line like 'x1,y1 -> x2,y2'"))?;
+
+        Ok(Self {
+            start: parse_coord(s, start.trim())?,
+            end: parse_coord(s, end.trim())?,
+        })
     }
 }
 
-fn step(a: i32, b: i32) -> i32 {
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{} -> {},{}",
+            self.start.0, self.start.1, self.end.0, self.end.1
+        )
+    }
+}
+
+pub fn step(a: i32, b: i32) -> i32 {
     match a.cmp(&b) {
         std::cmp::Ordering::Less => 1,
         std::cmp::Ordering::Equal => 0,
@@ -38,12 +55,13 @@ fn step(a: i32, b: i32) -> i32 {
     }
 }
 
-struct Board {
+#[derive(Default)]
+pub struct Board {
     pub entries: HashMap<(i32, i32), i32>,
 }
 
 impl Board {
-    fn draw_line(&mut self, line: &Line, diagonal: bool) {
+    pub fn draw_line(&mut self, line: &Line, diagonal: bool) {
         if !(diagonal || line.start.0 == line.end.0 || line.start.1 == line.end.1) {
             return;
         }
@@ -66,59 +84,97 @@ impl Board {
         }
     }
 
-    fn num_overlap(&self) -> i32 {
+    pub fn num_overlap(&self) -> i32 {
         self.entries.iter().filter(|(_, &val)| val > 1).count() as i32
     }
 }
 
-fn strings_to_board<'a, T: IntoIterator<Item = &'a str>>(lines: T, diagonal: bool) -> Board {
+pub fn lines_to_board(lines: &[Line], diagonal: bool) -> Board {
     let mut board = Board {
         entries: HashMap::new(),
     };
     lines
-        .into_iter()
-        .map(Line::from)
-        .for_each(|line| board.draw_line(&line, diagonal));
+        .iter()
+        .for_each(|line| board.draw_line(line, diagonal));
 
     board
 }
 
-pub fn part1() {
-    let input = BufReader::new(File::open("input/day5.txt").unwrap());
+pub fn strings_to_board<'a, T: IntoIterator<Item = &'a str>>(
+    lines: T,
+    diagonal: bool,
+) -> Result<Board> {
+    let lines = lines
+        .into_iter()
+        .map(Line::try_from)
+        .collect::<Result<Vec<Line>>>()?;
 
-    let strings = input
-        .lines()
-        .map(|line| line.unwrap())
-        .collect::<Vec<String>>();
+    Ok(lines_to_board(&lines, diagonal))
+}
 
-    let board = strings_to_board(strings.iter().map(|s| s as &str), false);
+pub struct Day5;
 
-    println!("Part1: {}", board.num_overlap());
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .map(|line| Line::try_from(line).map_err(|err| err.within(input, line)))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(lines_to_board(input, false).num_overlap().into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(lines_to_board(input, true).num_overlap().into())
+    }
+}
+
+/// Both boards, drawn as the lines stream past, so only the covered points are
+/// kept rather than every line.
+#[derive(Default)]
+pub struct Boards {
+    pub straight: Board,
+    pub all: Board,
 }
 
-pub fn part2() {
-    let input = BufReader::new(File::open("input/day5.txt").unwrap());
+impl Streaming for Day5 {
+    type Summary = Boards;
 
-    let strings = input
-        .lines()
-        .map(|line| line.unwrap())
-        .collect::<Vec<String>>();
+    fn add_line(summary: &mut Self::Summary, line: &str) -> Result<()> {
+        let line = Line::try_from(line)?;
+        summary.straight.draw_line(&line, false);
+        summary.all.draw_line(&line, true);
 
-    let board = strings_to_board(strings.iter().map(|s| s as &str), true);
+        Ok(())
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Result<Answer> {
+        Ok(summary.straight.num_overlap().into())
+    }
 
-    println!("Part2: {}", board.num_overlap());
+    fn summary_part2(summary: &Self::Summary) -> Result<Answer> {
+        Ok(summary.all.num_overlap().into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{generate, solution, store::fixture};
+    use proptest::prelude::*;
 
     #[test]
     fn parse() {
         let s = "964,133 -> 596,133";
 
         assert_eq!(
-            Line::from(s),
+            Line::try_from(s).unwrap(),
             Line {
                 start: (964, 133),
                 end: (596, 133)
@@ -126,6 +182,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_matches_parse() {
+        for input in [
+            fixture(5, "example"),
+            generate::generate(5, 200, 3).unwrap(),
+        ] {
+            let streamed = solution::solve_stream::<Day5>(&mut input.as_bytes(), None).unwrap();
+
+            assert_eq!(
+                streamed.answers,
+                solution::solve::<Day5>(&input, None).unwrap()
+            );
+        }
+
+        let err = solution::solve_stream::<Day5>(&mut "0,9 -> 5,9\n8,0 -> 0,x\n".as_bytes(), None)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Parse {
+                line: 2,
+                column: 10,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn draw() {
         let line = Line {
@@ -161,7 +243,7 @@ mod tests {
             "5,5 -> 8,2",
         ];
 
-        let board = strings_to_board(sample_input, false);
+        let board = strings_to_board(sample_input, false).unwrap();
 
         assert_eq!(5, board.num_overlap());
     }
@@ -181,8 +263,56 @@ mod tests {
             "5,5 -> 8,2",
         ];
 
-        let board = strings_to_board(sample_input, true);
+        let board = strings_to_board(sample_input, true).unwrap();
 
         assert_eq!(12, board.num_overlap());
     }
+
+    fn coord() -> impl Strategy<Value = (i32, i32)> {
+        (0..1000, 0..1000)
+    }
+
+    /// Horizontal, vertical or 45 degree lines, as in the puzzle input.
+    fn puzzle_line() -> impl Strategy<Value = Line> {
+        (coord(), 0..4, 0..300).prop_map(|(start, direction, length)| {
+            let (dx, dy) = [(1, 0), (0, 1), (1, 1), (1, -1)][direction as usize];
+
+            Line {
+                start,
+                end: (start.0 + dx * length, start.1 + dy * length),
+            }
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_display_round_trip(start in coord(), end in coord()) {
+            let line = Line { start, end };
+
+            prop_assert_eq!(Line::try_from(line.to_string().as_str()).unwrap(), line);
+        }
+
+        #[test]
+        fn parse_with_spacing(start in coord(), end in coord(), gap in " {0,3}") {
+            let s = format!(
+                "{}{},{}{}->{}{},{}",
+                gap, start.0, start.1, gap, gap, end.0, end.1
+            );
+
+            prop_assert_eq!(Line::try_from(s.as_str()).unwrap(), Line { start, end });
+        }
+
+        #[test]
+        fn draws_every_point(line in puzzle_line()) {
+            let board = lines_to_board(std::slice::from_ref(&line), true);
+            let length = (line.start.0 - line.end.0)
+                .abs()
+                .max((line.start.1 - line.end.1).abs());
+
+            prop_assert_eq!(board.entries.len() as i32, length + 1);
+            prop_assert!(board.entries.contains_key(&line.start));
+            prop_assert!(board.entries.contains_key(&line.end));
+            prop_assert_eq!(board.num_overlap(), 0);
+        }
+    }
 }
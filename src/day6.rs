@@ -1,67 +1,65 @@
-use std::{
-    fs::File,
-    io::{BufReader, Read},
+use crate::{
+    error::{parse_token, Error, Result},
+    solution::{Answer, Solution},
 };
 
-fn tick_fish(state: &mut [usize; 9]) {
+pub fn tick_fish(state: &mut [usize; 9]) {
     state.rotate_left(1);
     state[6] += state[8];
 }
 
-fn parse_input(s: &str) -> [usize; 9] {
+pub fn parse_input(s: &str) -> Result<[usize; 9]> {
     let mut fish_counts = [0; 9];
-    s.split(',')
-        .map(|s| s.parse::<u8>().expect("Not a number"))
-        .for_each(|x| fish_counts[x as usize] += 1);
+    for timer in s.split(',') {
+        let x = parse_token::<u8>(s, timer, "a timer")?;
+        if x > 8 {
+            return Err(Error::parse(s, timer, "expected a timer between 0 and 8"));
+        }
+
+        fish_counts[x as usize] += 1;
+    }
 
-    fish_counts
+    Ok(fish_counts)
 }
 
-fn simulate_days(initial_state: &mut [usize; 9], num_days: usize) {
+pub fn simulate_days(initial_state: &mut [usize; 9], num_days: usize) {
     for _ in 0..num_days {
         tick_fish(initial_state);
     }
 }
 
-pub fn part1() {
-    let mut input = BufReader::new(File::open("input/day6.txt").unwrap());
+pub struct Day6;
 
-    let mut input_string = String::new();
-    input
-        .read_to_string(&mut input_string)
-        .expect("FAiled to read line");
+impl Solution for Day6 {
+    const DAY: u8 = 6;
 
-    let mut fish_counts = parse_input(&input_string);
+    type Input = [usize; 9];
 
-    simulate_days(&mut fish_counts, 80);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.trim()).map_err(|err| err.within(input, input.trim()))
+    }
 
-    println!(
-        "Number of fish after 80 days: {}",
-        fish_counts.iter().sum::<usize>()
-    );
-}
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let mut fish_counts = *input;
 
-pub fn part2() {
-    let mut input = BufReader::new(File::open("input/day6.txt").unwrap());
+        simulate_days(&mut fish_counts, 80);
 
-    let mut input_string = String::new();
-    input
-        .read_to_string(&mut input_string)
-        .expect("FAiled to read line");
+        Ok(fish_counts.iter().sum::<usize>().into())
+    }
 
-    let mut fish_counts = parse_input(&input_string);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let mut fish_counts = *input;
 
-    simulate_days(&mut fish_counts, 256);
+        simulate_days(&mut fish_counts, 256);
 
-    println!(
-        "Number of fish after 256 days: {}",
-        fish_counts.iter().sum::<usize>()
-    );
+        Ok(fish_counts.iter().sum::<usize>().into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn steps() {
@@ -83,4 +81,39 @@ mod tests {
 
         assert_eq!(fish.iter().sum::<usize>(), 5934);
     }
+
+    fn fish_state() -> impl Strategy<Value = [usize; 9]> {
+        prop::array::uniform9(0..1_000_000_usize)
+    }
+
+    proptest! {
+        #[test]
+        fn fish_never_decrease(mut fish in fish_state(), days in 0..100_usize) {
+            let mut total = fish.iter().sum::<usize>();
+
+            for _ in 0..days {
+                tick_fish(&mut fish);
+
+                let new_total = fish.iter().sum::<usize>();
+                prop_assert!(new_total >= total);
+                total = new_total;
+            }
+        }
+
+        #[test]
+        fn parse_counts_timers(timers in prop::collection::vec(0..=8_u8, 1..200)) {
+            let input = timers
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<String>>()
+                .join(",");
+            let fish = parse_input(&input).unwrap();
+
+            prop_assert_eq!(fish.iter().sum::<usize>(), timers.len());
+            for timer in 0..=8 {
+                let count = timers.iter().filter(|&&t| t == timer).count();
+                prop_assert_eq!(fish[timer as usize], count);
+            }
+        }
+    }
 }
@@ -1,13 +1,15 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    error::{parse_token, Result},
+    solution::{Answer, Solution},
 };
 
-fn parse_input(s: &str) -> Vec<i32> {
-    s.split(',').map(|s| s.parse().unwrap()).collect()
+pub fn parse_input(s: &str) -> Result<Vec<i32>> {
+    s.split(',')
+        .map(|position| parse_token(s, position, "a position"))
+        .collect()
 }
 
-fn calculate_cost(x: i32, y: i32, part_1: bool) -> i32 {
+pub fn calculate_cost(x: i32, y: i32, part_1: bool) -> i32 {
     if part_1 {
         (x - y).abs()
     } else {
@@ -19,7 +21,7 @@ fn calculate_cost(x: i32, y: i32, part_1: bool) -> i32 {
     }
 }
 
-fn optimal_position(positions: &[i32], part_1: bool) -> (i32, i32) {
+pub fn optimal_position(positions: &[i32], part_1: bool) -> (i32, i32) {
     let &min = positions.iter().min().unwrap();
     let &max = positions.iter().max().unwrap();
 
@@ -37,34 +39,28 @@ fn optimal_position(positions: &[i32], part_1: bool) -> (i32, i32) {
         .unwrap()
 }
 
-pub fn part1() {
-    let mut input = BufReader::new(File::open("input/day7.txt").unwrap());
+pub struct Day7;
 
-    let mut input_str = String::new();
-    input
-        .read_line(&mut input_str)
-        .expect("Read first line of input");
+impl Solution for Day7 {
+    const DAY: u8 = 7;
 
-    let numbers = parse_input(&input_str);
+    type Input = Vec<i32>;
 
-    let (position, cost) = optimal_position(&numbers, true);
-
-    println!("Fuel cost to get to {} is {}", position, cost);
-}
-
-pub fn part2() {
-    let mut input = BufReader::new(File::open("input/day7.txt").unwrap());
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.trim()).map_err(|err| err.within(input, input.trim()))
+    }
 
-    let mut input_str = String::new();
-    input
-        .read_line(&mut input_str)
-        .expect("Read first line of input");
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let (_, cost) = optimal_position(input, true);
 
-    let numbers = parse_input(&input_str);
+        Ok(cost.into())
+    }
 
-    let (position, cost) = optimal_position(&numbers, false);
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let (_, cost) = optimal_position(input, false);
 
-    println!("Fuel cost to get to {} is {}", position, cost);
+        Ok(cost.into())
+    }
 }
 
 #[cfg(test)]
@@ -1,21 +1,30 @@
 use std::{
     convert::{TryFrom, TryInto},
-    fs::File,
-    io::BufRead,
-    io::BufReader,
     ops::{BitAnd, BitOr},
 };
 
-fn split_input(s: &str) -> (&str, &str) {
-    let mut it = s.split('|');
-    (it.next().unwrap().trim(), it.next().unwrap().trim())
+use crate::{
+    error::{Error, Result},
+    solution::{Answer, Solution},
+};
+
+pub fn split_input(s: &str) -> Result<(&str, &str)> {
+    let (input, output) = s
+        .split_once('|')
+        .ok_or_else(|| Error::parse(s, s.trim(), "expected 'signals | output'"))?;
+
+    Ok((input.trim(), output.trim()))
+}
+
+fn parse_pattern(source: &str, s: &str) -> Result<Pattern> {
+    Pattern::try_from(s).map_err(|reason| Error::parse(source, s, reason))
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-struct Pattern(u16);
+pub struct Pattern(pub u16);
 
 impl Pattern {
-    fn num_bits(&self) -> u32 {
+    pub fn num_bits(&self) -> u32 {
         (0..7)
             .map(|x| if (self.0 & (1 << x)) > 0 { 1 } else { 0 })
             .sum()
@@ -84,33 +93,24 @@ impl BitOr for Pattern {
     }
 }
 
-fn decode_entry(entry: &(&str, &str)) -> u32 {
+pub fn decode_entry(entry: &(&str, &str)) -> Result<u32> {
     let (input, output) = entry;
     let inputs: Vec<&str> = input.split_whitespace().collect();
 
-    let one_pattern = Pattern::try_from(
-        inputs
+    let unique_pattern = |len: usize, digit: u32| -> Result<Pattern> {
+        let signal = inputs
             .iter()
-            .find_map(|&x| if x.len() == 2 { Some(x) } else { None })
-            .expect("Pattern for 1"),
-    )
-    .expect("Convert pattern for 1");
+            .find_map(|&x| if x.len() == len { Some(x) } else { None })
+            .ok_or_else(|| {
+                Error::parse(input, input, format!("expected a pattern for {}", digit))
+            })?;
 
-    let four_pattern = Pattern::try_from(
-        inputs
-            .iter()
-            .find_map(|&x| if x.len() == 4 { Some(x) } else { None })
-            .expect("Pattern for 4"),
-    )
-    .expect("Convert pattern for 4");
+        parse_pattern(input, signal)
+    };
 
-    let seven_pattern = Pattern::try_from(
-        inputs
-            .iter()
-            .find_map(|&x| if x.len() == 3 { Some(x) } else { None })
-            .expect("Pattern for 7"),
-    )
-    .expect("Convert pattern for 7");
+    let one_pattern = unique_pattern(2, 1)?;
+    let four_pattern = unique_pattern(4, 4)?;
+    let seven_pattern = unique_pattern(3, 7)?;
 
     let mut patterns = [Pattern(0); 10];
     patterns[1] = one_pattern;
@@ -118,7 +118,7 @@ fn decode_entry(entry: &(&str, &str)) -> u32 {
     patterns[7] = seven_pattern;
 
     for input in inputs {
-        let input_pattern = Pattern::try_from(input).expect("invalid pattern");
+        let input_pattern = parse_pattern(input, input)?;
 
         match input_pattern.num_bits() {
             7 => patterns[8] = input_pattern,
@@ -151,62 +151,78 @@ fn decode_entry(entry: &(&str, &str)) -> u32 {
     }
 
     output
-        .trim()
         .split_whitespace()
-        .filter_map(|x| Pattern::try_from(x).ok())
-        .map(|p| {
+        .map(|x| {
+            let p = parse_pattern(output, x)?;
             patterns
                 .iter()
                 .position(|&p_candidate| p == p_candidate)
-                .unwrap()
+                .ok_or_else(|| Error::unsolvable(format!("output {} matches no digit", x)))
         })
-        .reduce(|total, num| (total * 10) + num)
-        .unwrap() as u32
+        .try_fold(0, |total, num| Ok((total * 10) + num? as u32))
 }
 
-fn count_unique_numbers(lines: &[String]) -> usize {
+pub fn count_unique_numbers(lines: &[String]) -> Result<usize> {
     let unique_number_signals: [u32; 4] = [2, 3, 4, 7];
-    lines
-        .iter()
-        .map(|line| {
-            let (_, output) = split_input(line);
-            output
-        })
-        .map(|s| {
-            s.split_whitespace()
+    lines.iter().try_fold(0, |total, line| {
+        let (_, output) = split_input(line)?;
+
+        Ok(total
+            + output
+                .split_whitespace()
                 .map(|s| s.len())
                 .filter(|&len| unique_number_signals.contains(&(len as u32)))
-                .count()
-        })
-        .sum::<usize>()
+                .count())
+    })
 }
 
-pub fn part1() {
-    let input_file = BufReader::new(File::open("input/day8.txt").unwrap());
+/// Checks an entry has ten valid signal patterns and four valid output patterns.
+fn check_entry(line: &str) -> Result<()> {
+    let (input, output) = split_input(line)?;
 
-    let full_input: Vec<String> = input_file
-        .lines()
-        .map(|result| result.expect("valid line"))
-        .collect();
+    for (patterns, count) in [(input, 10), (output, 4)] {
+        let words = patterns.split_whitespace().collect::<Vec<&str>>();
+        if words.len() != count {
+            let reason = format!("expected {} patterns", count);
+            return Err(Error::parse(line, patterns, reason));
+        }
 
-    let count = count_unique_numbers(&full_input);
+        for word in words {
+            parse_pattern(line, word)?;
+        }
+    }
 
-    println!("Number of unique numbers {}", count);
+    Ok(())
 }
-pub fn part2() {
-    let input_file = BufReader::new(File::open("input/day8.txt").unwrap());
-    let lines: Vec<String> = input_file
-        .lines()
-        .map(|result| result.expect("valid line"))
-        .collect();
-
-    let sum = lines
-        .iter()
-        .map(|line| split_input(line))
-        .map(|entry| decode_entry(&entry))
-        .sum::<u32>();
 
-    println!("Sum of decoded output: {}", sum);
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .map(|line| {
+                check_entry(line).map_err(|err| err.within(input, line))?;
+
+                Ok(line.to_string())
+            })
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_unique_numbers(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        input
+            .iter()
+            .try_fold(0, |sum, line| Ok(sum + decode_entry(&split_input(line)?)?))
+            .map(|sum: u32| sum.into())
+    }
 }
 
 #[cfg(test)]
@@ -217,7 +233,7 @@ mod tests {
     fn split_input_test() {
         let s = "bgeacd dbfag bcadegf agdce dgfbce bgc bdgca aedcgf bc abec | gcdfbe cbea bc gbc";
 
-        let (first, second) = split_input(s);
+        let (first, second) = split_input(s).unwrap();
 
         assert_eq!(
             first,
@@ -229,7 +245,7 @@ mod tests {
 
     #[test]
     fn sample_data_part1() {
-        let test_data_part1 = vec![
+        let test_data_part1 = [
 "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe",
 "edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc    ",
 "fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg         ",
@@ -245,7 +261,7 @@ mod tests {
         .map(|s| s.to_string())
         .collect::<Vec<String>>();
 
-        let c = count_unique_numbers(&test_data_part1);
+        let c = count_unique_numbers(&test_data_part1).unwrap();
         assert_eq!(c, 26);
     }
 
@@ -254,16 +270,16 @@ mod tests {
         let test_data =
             "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
 
-        let entry = split_input(test_data);
+        let entry = split_input(test_data).unwrap();
 
-        let output = decode_entry(&entry);
+        let output = decode_entry(&entry).unwrap();
 
         assert_eq!(output, 5353);
     }
 
     #[test]
     fn sample_data_part2() {
-        let lines = vec![
+        let lines = [
             "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe",
             "edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc    ",
             "fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg         ",
@@ -280,8 +296,8 @@ mod tests {
         .collect::<Vec<String>>();
         let sum = lines
             .iter()
-            .map(|line| split_input(&line))
-            .map(|entry| decode_entry(&entry))
+            .map(|line| split_input(line).unwrap())
+            .map(|entry| decode_entry(&entry).unwrap())
             .sum::<u32>();
 
         assert_eq!(sum, 61229);
@@ -1,213 +1,154 @@
-use std::{fs::File, io::BufRead, io::BufReader};
+use std::collections::HashSet;
 
-struct HeightMap {
-    data: Vec<u8>,
-    width: u32,
+use crate::{
+    error::{Error, Result},
+    grid::Grid,
+    render::{self, Frame, Render, Rgb},
+    solution::{Answer, Solution},
+};
+
+const RIDGE: Rgb = [90, 90, 90];
+const LOW_POINT: Rgb = [255, 255, 255];
+
+pub struct HeightMap {
+    pub heights: Grid<u8>,
 }
 
 impl HeightMap {
-    fn index(&self, (x, y): (u32, u32)) -> usize {
-        ((y * self.width) + x) as usize
-    }
-    fn coord(&self, index: usize) -> (u32, u32) {
-        (index as u32 % self.width, index as u32 / self.width)
-    }
-    fn low_points(&self) -> Vec<(u32, u32)> {
-        let directions = [(-1, 0), (0, 1), (1, 0), (0, -1)];
-        let map_height = self.data.len() / self.width as usize;
-
-        self.data
-            .iter()
-            .enumerate()
-            .filter_map(|(i, &height)| {
-                let (x, y) = self.coord(i);
-
-                let all_lower = directions
-                    .iter()
-                    .filter_map(|&(dx, dy)| {
-                        let (x, y) = (x as i32 + dx, y as i32 + dy);
-                        if x < 0 || x >= self.width as i32 || y < 0 || y >= map_height as i32 {
-                            return None;
-                        }
-
-                        let coord = (x as u32, y as u32);
-                        let val = self.data[self.index(coord)];
-                        if val <= height {
-                            Some(false)
-                        } else {
-                            Some(true)
-                        }
-                    })
-                    .all(|x| x);
-
-                if all_lower {
-                    Some((x, y))
-                } else {
-                    None
-                }
+    pub fn low_points(&self) -> Vec<(usize, usize)> {
+        self.heights
+            .cells()
+            .filter(|&(pos, &height)| {
+                self.heights
+                    .neighbours4(pos)
+                    .all(|neighbour| self.heights[neighbour] > height)
             })
+            .map(|(pos, _)| pos)
             .collect()
     }
-    fn risk_level(&self) -> u32 {
+    pub fn risk_level(&self) -> u32 {
         let low_points = self.low_points();
         low_points
             .iter()
-            .map(|&coord| self.index(coord))
-            .map(|i| self.data[i])
+            .map(|&pos| self.heights[pos])
             .map(|height| (height + 1) as u32)
             .sum()
     }
 
-    fn neighbours(&self, coord: (u32, u32)) -> Vec<(u32, u32)> {
-        let height = self.data.len() as u32 / self.width;
-        let (x, y) = coord;
+    pub fn find_basin(&self, low_point: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut basin = Vec::new();
 
-        let mut neighbours = Vec::new();
+        let mut frontier = vec![low_point];
 
-        if x > 0 {
-            neighbours.push((x - 1, y));
-        }
-        if y > 0 {
-            neighbours.push((x, y - 1))
-        }
-        if x < (self.width - 1) {
-            neighbours.push((x + 1, y))
-        }
-        if y < (height - 1) {
-            neighbours.push((x, y + 1))
+        while let Some(pos) = frontier.pop() {
+            if basin.contains(&pos) {
+                continue;
+            }
+            basin.push(pos);
+            self.heights
+                .neighbours4(pos)
+                .filter(|new_pos| !basin.contains(new_pos) && self.heights[*new_pos] != 9)
+                .for_each(|pos| frontier.push(pos));
         }
 
-        neighbours
+        basin
     }
 
-    fn find_basin(&self, low_point: (u32, u32)) -> Vec<(u32, u32)> {
-        let mut basin = Vec::new();
+    /// The index in [`low_points`](Self::low_points) of the basin each position
+    /// belongs to, or `None` for the 9s between basins.
+    pub fn basins(&self) -> Grid<Option<usize>> {
+        let mut basins = self.heights.map(|_| None);
 
-        let mut frontier = vec![low_point];
+        for (basin, low_point) in self.low_points().into_iter().enumerate() {
+            let mut frontier = vec![low_point];
 
-        while !frontier.is_empty() {
-            let coord = frontier.pop().unwrap();
-            if basin.contains(&coord) {
-                continue;
+            while let Some(pos) = frontier.pop() {
+                if basins[pos].is_some() || self.heights[pos] == 9 {
+                    continue;
+                }
+                basins[pos] = Some(basin);
+                frontier.extend(self.heights.neighbours4(pos));
             }
-            basin.push(coord);
-            self.neighbours(coord)
-                .iter()
-                .filter(|&&new_coord| {
-                    !basin.contains(&new_coord) && self.data[self.index(new_coord)] != 9
-                })
-                .for_each(|&coord| frontier.push(coord));
         }
 
-        basin
+        basins
     }
 }
 
-pub fn part1() {
-    let input = BufReader::new(File::open("input/day9.txt").expect("No input found"));
+pub struct Day9;
 
-    let width = 100;
-    let height = 100;
-    let mut data: Vec<u8> = Vec::new();
-    data.reserve(width * height);
+impl Solution for Day9 {
+    const DAY: u8 = 9;
 
-    input.lines().for_each(|line| {
-        let line = line.expect("invalid line");
-        line.trim()
-            .chars()
-            .map(|c| c.to_digit(10).expect("invalid digit") as u8)
-            .for_each(|x| data.push(x));
-    });
+    type Input = HeightMap;
 
-    let board = HeightMap {
-        data,
-        width: width as u32,
-    };
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(HeightMap {
+            heights: Grid::parse_digits(input)?,
+        })
+    }
 
-    println!("Risk level: {}", board.risk_level());
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(input.risk_level().into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let board = input;
+
+        let mut basin_sizes = board
+            .low_points()
+            .iter()
+            .map(|&coord| board.find_basin(coord).len())
+            .collect::<Vec<usize>>();
+        basin_sizes.sort_unstable();
+        let product = basin_sizes
+            .iter()
+            .rev()
+            .take(3)
+            .copied()
+            .reduce(|product, num| product * num)
+            .ok_or_else(|| Error::unsolvable("no basins found"))?;
+
+        Ok(product.into())
+    }
 }
 
-pub fn part2() {
-    let input = BufReader::new(File::open("input/day9.txt").expect("No input found"));
-
-    let width = 100;
-    let height = 100;
-    let mut data: Vec<u8> = Vec::new();
-    data.reserve(width * height);
-
-    input.lines().for_each(|line| {
-        let line = line.expect("invalid line");
-        line.trim()
-            .chars()
-            .map(|c| c.to_digit(10).expect("invalid digit") as u8)
-            .for_each(|x| data.push(x));
-    });
-
-    let board = HeightMap {
-        data,
-        width: width as u32,
-    };
-
-    let mut basin_sizes = board
-        .low_points()
-        .iter()
-        .map(|&coord| board.find_basin(coord).len())
-        .collect::<Vec<usize>>();
-    basin_sizes.sort_unstable();
-    let product = basin_sizes
-        .iter()
-        .rev()
-        .take(3)
-        .copied()
-        .reduce(|product, num| product * num)
-        .expect("product failed");
-
-    println!("Product of 3 largest basins: {}", product);
+/// Each basin in its own colour, darker higher up, with the low points in white
+/// and the ridges of 9s in grey.
+impl Render for Day9 {
+    fn frames(input: &Self::Input) -> Result<Vec<Frame>> {
+        let basins = input.basins();
+        let low_points = input.low_points().into_iter().collect::<HashSet<_>>();
+
+        let frame = render::draw(&input.heights, |pos, &height| match basins[pos] {
+            _ if low_points.contains(&pos) => LOW_POINT,
+            Some(basin) => render::blend(
+                render::LABELS[basin % render::LABELS.len()],
+                render::BACKGROUND,
+                height as f64 / 12.0,
+            ),
+            None => RIDGE,
+        });
+
+        Ok(vec![frame])
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn index_test() {
-        let heightmap = HeightMap {
-            data: Vec::new(),
-            width: 10,
-        };
-        let xy = (5, 5);
-        assert_eq!(heightmap.index(xy), 55);
-    }
-
-    #[test]
-    fn coord_test() {
-        let heightmap = HeightMap {
-            data: Vec::new(),
-            width: 10,
-        };
-
-        let index = 55;
-        assert_eq!(heightmap.coord(index), (5, 5));
-    }
+    use crate::store::fixture;
 
     #[test]
     fn low_point_test() {
-        let data = vec![
-            2, 1, 9, 9, 9, 4, 3, 2, 1, 0, 3, 9, 8, 7, 8, 9, 4, 9, 2, 1, 9, 8, 5, 6, 7, 8, 9, 8, 9,
-            2, 8, 7, 6, 7, 8, 9, 6, 7, 8, 9, 9, 8, 9, 9, 9, 6, 5, 6, 7, 8,
-        ];
-        let heightmap = HeightMap { data, width: 10 };
+        let heightmap = Day9::parse(&fixture(9, "example")).unwrap();
         let low_points = heightmap.low_points();
         assert_eq!(low_points.len(), 4);
     }
 
     #[test]
     fn risk_level_test() {
-        let data = vec![
-            2, 1, 9, 9, 9, 4, 3, 2, 1, 0, 3, 9, 8, 7, 8, 9, 4, 9, 2, 1, 9, 8, 5, 6, 7, 8, 9, 8, 9,
-            2, 8, 7, 6, 7, 8, 9, 6, 7, 8, 9, 9, 8, 9, 9, 9, 6, 5, 6, 7, 8,
-        ];
-        let heightmap = HeightMap { data, width: 10 };
+        let heightmap = Day9::parse(&fixture(9, "example")).unwrap();
 
         let risk_level = heightmap.risk_level();
         assert_eq!(risk_level, 15);
@@ -215,11 +156,7 @@ mod tests {
 
     #[test]
     fn find_basin_test() {
-        let data = vec![
-            2, 1, 9, 9, 9, 4, 3, 2, 1, 0, 3, 9, 8, 7, 8, 9, 4, 9, 2, 1, 9, 8, 5, 6, 7, 8, 9, 8, 9,
-            2, 8, 7, 6, 7, 8, 9, 6, 7, 8, 9, 9, 8, 9, 9, 9, 6, 5, 6, 7, 8,
-        ];
-        let heightmap = HeightMap { data, width: 10 };
+        let heightmap = Day9::parse(&fixture(9, "example")).unwrap();
 
         let low_point = (1, 0);
         assert_eq!(heightmap.find_basin(low_point).len(), 3);
@@ -233,4 +170,29 @@ mod tests {
         let low_point = (8, 4);
         assert_eq!(heightmap.find_basin(low_point).len(), 9);
     }
+
+    #[test]
+    fn basins_test() {
+        let heightmap = Day9::parse(&fixture(9, "example")).unwrap();
+        let basins = heightmap.basins();
+
+        let mut sizes = vec![0; 4];
+        basins.iter().flatten().for_each(|&basin| sizes[basin] += 1);
+        assert_eq!(sizes, vec![3, 9, 14, 9]);
+        assert_eq!(basins[(2, 0)], None);
+        assert_eq!(basins[(2, 2)], Some(2));
+    }
+
+    #[test]
+    fn parse_dimensions() {
+        let heightmap = Day9::parse("219\n398\n").unwrap();
+        assert_eq!(heightmap.heights.dimensions(), (3, 2));
+        assert_eq!(heightmap.low_points(), vec![(1, 0), (2, 1)]);
+
+        let err = Day9::parse("219\n39\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 0 line 2 column 1: expected a row of 3 digits (found \"39\")"
+        );
+    }
 }
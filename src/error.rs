@@ -0,0 +1,327 @@
+use std::{
+    fmt,
+    io::{self, BufRead},
+    str::FromStr,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    /// The input text could not be parsed. `line` and `column` are 1-based and
+    /// `text` is the offending part of the input.
+    Parse {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    /// The input parsed, but has no answer (for example no bingo board ever wins).
+    Unsolvable {
+        day: u8,
+        reason: String,
+    },
+    Io(io::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Byte offset of `token` inside `source`, or 0 if it isn't a slice of it.
+fn offset_of(source: &str, token: &str) -> usize {
+    let start = source.as_ptr() as usize;
+    let position = token.as_ptr() as usize;
+
+    if position >= start && position <= start + source.len() {
+        position - start
+    } else {
+        0
+    }
+}
+
+/// 1-based line and column of the byte `offset` in `source`.
+fn position_of(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// `text` with a caret under its 1-based `column` on the next line.
+fn caret_under(text: &str, column: usize) -> String {
+    format!("{}\n{}^", text, " ".repeat(column.saturating_sub(1)))
+}
+
+impl Error {
+    /// A parse error at `token`, which should be a slice of `source`. The position
+    /// is relative to the start of `source`.
+    pub fn parse(source: &str, token: &str, reason: impl Into<String>) -> Self {
+        let (line, column) = position_of(source, offset_of(source, token));
+
+        Error::Parse {
+            day: 0,
+            line,
+            column,
+            text: token.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn unsolvable(reason: impl Into<String>) -> Self {
+        Error::Unsolvable {
+            day: 0,
+            reason: reason.into(),
+        }
+    }
+
+    /// Moves a parse error to `token`, a slice of `source`, keeping its reason.
+    pub fn at(self, source: &str, token: &str) -> Self {
+        match self {
+            Error::Parse { day, reason, .. } => Error::parse(source, token, reason).in_day(day),
+            other => other,
+        }
+    }
+
+    /// Rebases a parse error found while parsing `inner` so that its position is
+    /// relative to `outer`, which `inner` is a slice of.
+    pub fn within(self, outer: &str, inner: &str) -> Self {
+        match self {
+            Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                reason,
+            } => {
+                let (inner_line, inner_column) = position_of(outer, offset_of(outer, inner));
+
+                Error::Parse {
+                    day,
+                    line: line + inner_line - 1,
+                    column: if line == 1 {
+                        column + inner_column - 1
+                    } else {
+                        column
+                    },
+                    text,
+                    reason,
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Moves a parse error found in a single line to line `number` of the whole
+    /// input.
+    pub fn on_line(self, number: usize) -> Self {
+        match self {
+            Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                reason,
+            } => Error::Parse {
+                day,
+                line: line + number - 1,
+                column,
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// Tags the error with the day it came from.
+    pub fn in_day(self, number: u8) -> Self {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                text,
+                reason,
+                ..
+            } => Error::Parse {
+                day: number,
+                line,
+                column,
+                text,
+                reason,
+            },
+            Error::Unsolvable { reason, .. } => Error::Unsolvable {
+                day: number,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// The line of `source` a parse error points at, with a caret under the
+    /// offending column.
+    pub fn excerpt(&self, source: &str) -> Option<String> {
+        match self {
+            Error::Parse { line, column, .. } => {
+                let text = source.lines().nth(line.checked_sub(1)?)?;
+                Some(caret_under(text, *column))
+            }
+            _ => None,
+        }
+    }
+
+    /// Like [`Error::excerpt`], but reads `reader` only as far as the offending
+    /// line, for inputs too big to keep in memory.
+    pub fn excerpt_reader<R: BufRead>(&self, reader: R) -> Option<String> {
+        match self {
+            Error::Parse { line, column, .. } => {
+                let text = reader.lines().nth(line.checked_sub(1)?)?.ok()?;
+                Some(caret_under(&text, *column))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parses `token`, a slice of `source`, into a `T`, describing what was `expected`
+/// if it doesn't parse.
+pub fn parse_token<T: FromStr>(source: &str, token: &str, expected: &str) -> Result<T> {
+    token
+        .parse()
+        .map_err(|_| Error::parse(source, token, format!("expected {}", expected)))
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                reason,
+            } => write!(
+                f,
+                "day {} line {} column {}: {} (found {:?})",
+                day, line, column, reason, text
+            ),
+            Error::Unsolvable { day, reason } => write!(f, "day {}: {}", day, reason),
+            Error::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// The line, column and text a parse error points at, for tests.
+#[cfg(test)]
+pub(crate) fn position(err: &Error) -> (usize, usize, &str) {
+    match err {
+        Error::Parse {
+            line, column, text, ..
+        } => (*line, *column, text),
+        _ => panic!("Not a parse error"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_position() {
+        let source = "1,2\n3,x\n";
+        let token = &source[6..7];
+
+        let err = Error::parse(source, token, "not a number");
+        assert_eq!(position(&err), (2, 3, "x"));
+
+        let end = &source[source.len()..];
+        let err = Error::parse(source, end, "missing line");
+        assert_eq!(position(&err), (3, 1, ""));
+    }
+
+    #[test]
+    fn within_test() {
+        let input = "header\n\n1 2\n3 x";
+        let block = &input[8..];
+
+        let err = Error::parse(block, &block[6..], "not a number");
+        assert_eq!(position(&err), (2, 3, "x"));
+        assert_eq!(position(&err.within(input, block)), (4, 3, "x"));
+
+        let line = &input[8..11];
+        let err = Error::parse(line, &line[1..2], "bad separator");
+        assert_eq!(position(&err.within(input, line)), (3, 2, " "));
+    }
+
+    #[test]
+    fn on_line_test() {
+        let input = "1,2\n3,x,5\n";
+        let line = "3,x,5";
+        let err = Error::parse(line, &line[2..3], "expected a number").on_line(2);
+
+        assert_eq!(position(&err), (2, 3, "x"));
+        assert_eq!(err.excerpt(input), Some("3,x,5\n  ^".to_string()));
+        assert_eq!(
+            err.excerpt_reader(input.as_bytes()),
+            Some("3,x,5\n  ^".to_string())
+        );
+    }
+
+    #[test]
+    fn at_test() {
+        let input = "ABC";
+        let err = Error::parse("", "", "not enough bits").at(input, &input[2..]);
+
+        assert_eq!(position(&err), (1, 3, "C"));
+    }
+
+    #[test]
+    fn parse_token_test() {
+        let line = "12,ab";
+
+        assert_eq!(
+            parse_token::<u32>(line, &line[..2], "a number").unwrap(),
+            12
+        );
+
+        let err = parse_token::<u32>(line, &line[3..], "a number").unwrap_err();
+        assert_eq!(position(&err), (1, 4, "ab"));
+        assert!(err.to_string().contains("expected a number"));
+    }
+
+    #[test]
+    fn excerpt_test() {
+        let source = "1,2\n3,x,5";
+        let err = Error::parse(source, &source[6..7], "expected a number");
+
+        assert_eq!(err.excerpt(source), Some("3,x,5\n  ^".to_string()));
+        assert_eq!(Error::unsolvable("no answer").excerpt(source), None);
+    }
+
+    #[test]
+    fn display_test() {
+        let line = "0,9 -> x,9";
+        let err = Error::parse(line, &line[7..8], "expected a number").in_day(5);
+
+        assert_eq!(
+            err.to_string(),
+            "day 5 line 1 column 8: expected a number (found \"x\")"
+        );
+        assert_eq!(
+            Error::unsolvable("no board wins").in_day(4).to_string(),
+            "day 4: no board wins"
+        );
+    }
+}
@@ -0,0 +1,577 @@
+//! Random puzzle inputs of any size, for stress tests and benchmarks. The same
+//! day, size and seed always give the same input.
+
+use std::fmt::Write;
+
+use crate::{
+    day11::Day11,
+    day16::{self, Header, Operator, Packet, PacketType},
+    solution::Solution,
+};
+
+/// A small SplitMix64 generator. Its output depends only on the seed, so
+/// generated inputs stay the same across platforms and crate versions.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low..high`, which must not be empty.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low < high, "empty range {}..{}", low, high);
+        low + self.next_u64() % (high - low)
+    }
+
+    /// An index into a collection of `len` items.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0, len as u64) as usize
+    }
+
+    /// True one time in `n`.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.range(0, n) == 0
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// Generates an input for `day` from `seed`, or `None` if there is no
+/// generator for that day. What `size` counts depends on the day; see the
+/// functions below.
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    let size = size.max(1);
+
+    Some(match day {
+        1 => day1(rng, size),
+        2 => day2(rng, size),
+        3 => day3(rng, size),
+        4 => day4(rng, size),
+        5 => day5(rng, size),
+        6 => day6(rng, size),
+        7 => day7(rng, size),
+        8 => day8(rng, size),
+        9 => day9(rng, size),
+        10 => day10(rng, size),
+        11 => day11(rng, size),
+        12 => day12(rng, size),
+        13 => day13(rng, size),
+        14 => day14(rng, size),
+        15 => day15(rng, size),
+        16 => day16(rng, size),
+        _ => return None,
+    })
+}
+
+fn join_lines(lines: impl Iterator<Item = String>) -> String {
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
+fn comma_list(numbers: impl Iterator<Item = u64>) -> String {
+    let numbers = numbers.map(|n| n.to_string()).collect::<Vec<String>>();
+    format!("{}\n", numbers.join(","))
+}
+
+/// A `size` by `size` grid of digits in `low..high`.
+fn digit_grid(rng: &mut Rng, size: usize, low: u64, high: u64) -> String {
+    join_lines((0..size).map(|_| {
+        (0..size)
+            .map(|_| rng.range(low, high).to_string())
+            .collect()
+    }))
+}
+
+/// `size` sonar depths that wander up and down.
+pub fn day1(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.range(100, 200);
+
+    join_lines((0..size).map(|_| {
+        depth = (depth + rng.range(0, 21)).saturating_sub(8);
+        depth.to_string()
+    }))
+}
+
+/// `size` submarine commands.
+pub fn day2(rng: &mut Rng, size: usize) -> String {
+    join_lines((0..size).map(|_| {
+        let command = rng.pick(&["forward", "down", "down", "up"]);
+        format!("{} {}", command, rng.range(1, 10))
+    }))
+}
+
+/// `size` distinct binary numbers, wide enough to hold twice as many.
+pub fn day3(rng: &mut Rng, size: usize) -> String {
+    let width = (usize::BITS - (2 * size).leading_zeros()).clamp(5, 31) as usize;
+    let count = size.min(1 << width);
+
+    let mut numbers = (0..1_u64 << width).collect::<Vec<u64>>();
+    rng.shuffle(&mut numbers);
+
+    join_lines(
+        numbers[..count]
+            .iter()
+            .map(|n| format!("{:0width$b}", n, width = width)),
+    )
+}
+
+/// Draws of every number on the boards, followed by `size` 5x5 boards.
+pub fn day4(rng: &mut Rng, size: usize) -> String {
+    let limit = 100;
+    let mut draws = (0..limit).collect::<Vec<u64>>();
+    rng.shuffle(&mut draws);
+
+    let mut text = comma_list(draws.iter().copied());
+    for _ in 0..size {
+        let mut numbers = (0..limit).collect::<Vec<u64>>();
+        rng.shuffle(&mut numbers);
+
+        text.push('\n');
+        for row in numbers[..25].chunks(5) {
+            let row = row
+                .iter()
+                .map(|n| format!("{:>2}", n))
+                .collect::<Vec<String>>();
+            let _ = writeln!(text, "{}", row.join(" "));
+        }
+    }
+
+    text
+}
+
+/// `size` horizontal, vertical and diagonal vent lines on a 1000x1000 floor.
+pub fn day5(rng: &mut Rng, size: usize) -> String {
+    join_lines((0..size).map(|_| {
+        let (x1, y1) = (rng.range(0, 1000) as i64, rng.range(0, 1000) as i64);
+        let (dx, dy) = rng.pick(&[(1, 0), (0, 1), (1, 1), (1, -1), (-1, 0), (0, -1)]);
+
+        // Stop where the line would leave the floor
+        let room = |start: i64, step: i64| match step {
+            1 => 999 - start,
+            -1 => start,
+            _ => i64::MAX,
+        };
+        let length = (rng.range(1, 300) as i64)
+            .min(room(x1, dx))
+            .min(room(y1, dy));
+
+        format!("{},{} -> {},{}", x1, y1, x1 + dx * length, y1 + dy * length)
+    }))
+}
+
+/// `size` lanternfish timers.
+pub fn day6(rng: &mut Rng, size: usize) -> String {
+    comma_list((0..size).map(|_| rng.range(1, 6)))
+}
+
+/// `size` crab positions.
+pub fn day7(rng: &mut Rng, size: usize) -> String {
+    let spread = (size as u64).clamp(10, 2_000);
+    comma_list((0..size).map(|_| rng.range(0, spread)))
+}
+
+/// `digit`'s segments through `wiring`, in a random order.
+fn scramble(rng: &mut Rng, wiring: &[char], digit: &str) -> String {
+    let mut segments = digit
+        .bytes()
+        .map(|c| wiring[(c - b'a') as usize])
+        .collect::<Vec<char>>();
+    rng.shuffle(&mut segments);
+
+    segments.into_iter().collect()
+}
+
+/// `size` notes of scrambled seven-segment displays.
+pub fn day8(rng: &mut Rng, size: usize) -> String {
+    const DIGITS: [&str; 10] = [
+        "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+    ];
+
+    join_lines((0..size).map(|_| {
+        let mut wiring = "abcdefg".chars().collect::<Vec<char>>();
+        rng.shuffle(&mut wiring);
+
+        let mut patterns = DIGITS
+            .iter()
+            .map(|digit| scramble(rng, &wiring, digit))
+            .collect::<Vec<String>>();
+        rng.shuffle(&mut patterns);
+        let output = (0..4)
+            .map(|_| {
+                let digit = DIGITS[rng.index(10)];
+                scramble(rng, &wiring, digit)
+            })
+            .collect::<Vec<String>>();
+
+        format!("{} | {}", patterns.join(" "), output.join(" "))
+    }))
+}
+
+/// Where the ridges of 9s cross one axis of a day 9 height map, as `None`, and
+/// for every other position, where its basin's low point is along that axis.
+fn basin_lows(rng: &mut Rng, size: usize) -> Vec<Option<usize>> {
+    let mut lows = Vec::with_capacity(size);
+    while lows.len() < size {
+        let start = lows.len();
+        let end = (start + rng.range(2, 11) as usize).min(size);
+        let low = rng.range(start as u64, end as u64) as usize;
+        lows.extend((start..end).map(|_| Some(low)));
+
+        if lows.len() < size {
+            lows.push(None);
+        }
+    }
+
+    lows
+}
+
+/// A `size` by `size` height map like the puzzle's, with basins walled off by
+/// 9s that each rise from a single low point. Random heights would run into a
+/// few huge basins with many low points each.
+pub fn day9(rng: &mut Rng, size: usize) -> String {
+    let rows = basin_lows(rng, size);
+    let columns = basin_lows(rng, size);
+
+    join_lines(rows.iter().enumerate().map(|(y, row_low)| {
+        columns
+            .iter()
+            .enumerate()
+            .map(|(x, column_low)| match (row_low, column_low) {
+                (Some(low_y), Some(low_x)) => {
+                    (y.abs_diff(*low_y) + x.abs_diff(*low_x)).min(8).to_string()
+                }
+                _ => "9".to_string(),
+            })
+            .collect()
+    }))
+}
+
+/// `size` lines of chunks, each either corrupted or incomplete.
+pub fn day10(rng: &mut Rng, size: usize) -> String {
+    const OPEN: [char; 4] = ['(', '[', '{', '<'];
+    const CLOSE: [char; 4] = [')', ']', '}', '>'];
+
+    join_lines((0..size).map(|_| {
+        let length = rng.range(10, 100);
+        let corrupt_at = if rng.one_in(2) {
+            Some(rng.range(0, length))
+        } else {
+            None
+        };
+
+        let mut line = String::new();
+        let mut stack = Vec::new();
+        for i in 0..length {
+            if stack.is_empty() || rng.one_in(2) {
+                let chunk = rng.index(4);
+                stack.push(chunk);
+                line.push(OPEN[chunk]);
+            } else if Some(i) == corrupt_at {
+                let expected = stack[stack.len() - 1];
+                line.push(CLOSE[(expected + rng.range(1, 4) as usize) % 4]);
+            } else if let Some(chunk) = stack.pop() {
+                line.push(CLOSE[chunk]);
+            }
+        }
+
+        if stack.is_empty() {
+            line.push(OPEN[rng.index(4)]);
+        }
+        line
+    }))
+}
+
+/// A `size` by `size` grid of octopus energy levels that all flash at once.
+/// Random grids up to the puzzle's 10x10 are redrawn until they do within a
+/// thousand steps. Larger random grids almost never do, so those are built to:
+/// octopuses at 9 flash together, pulling in any lower one whose neighbours
+/// are all at 9, and taking the same amount off every level only delays that.
+pub fn day11(rng: &mut Rng, size: usize) -> String {
+    if size <= 10 {
+        let mut grid = digit_grid(rng, size, 0, 10);
+        for _ in 0..100 {
+            let synchronises =
+                Day11::parse(&grid).is_ok_and(|board| board.first_sync(1_000).is_some());
+            if synchronises {
+                break;
+            }
+            grid = digit_grid(rng, size, 0, 10);
+        }
+        return grid;
+    }
+
+    let delay = rng.range(0, 9);
+    let mut levels = vec![vec![9; size]; size];
+    for y in 0..size {
+        for x in 0..size {
+            let around = |at: usize| at.saturating_sub(1)..=(at + 1).min(size - 1);
+            let neighbours = around(y).count() * around(x).count() - 1;
+            // Lower octopuses are kept apart so that everything around them flashes
+            let lower_before =
+                around(x).any(|x| y > 0 && levels[y - 1][x] < 9) || (x > 0 && levels[y][x - 1] < 9);
+
+            if !lower_before && rng.one_in(3) {
+                let lowest = (9 - neighbours as u64).max(delay);
+                levels[y][x] = rng.range(lowest, 10);
+            }
+        }
+    }
+
+    join_lines(levels.iter().map(|row| {
+        row.iter()
+            .map(|level| (level - delay).to_string())
+            .collect()
+    }))
+}
+
+/// A lowercase name for the cave numbered `n`.
+fn cave_name(mut n: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+        if n == 0 {
+            return name;
+        }
+    }
+}
+
+/// A cave system of `size` small caves, a big cave for every four small ones,
+/// and `start` and `end`. The number of paths grows very quickly with `size`.
+pub fn day12(rng: &mut Rng, size: usize) -> String {
+    let small = (0..size)
+        .map(|n| format!("{}x", cave_name(n)))
+        .collect::<Vec<String>>();
+    let big = (0..size.div_ceil(4))
+        .map(|n| format!("{}X", cave_name(n).to_uppercase()))
+        .collect::<Vec<String>>();
+
+    let mut connections = vec![
+        format!("start-{}", small[0]),
+        format!("{}-end", small[rng.index(size)]),
+    ];
+
+    // A random tree keeps every small cave reachable, then each big cave joins
+    // two small ones
+    for i in 1..size {
+        connections.push(format!("{}-{}", small[rng.index(i)], small[i]));
+    }
+    for cave in &big {
+        let a = rng.index(size);
+        let b = rng.index(size);
+        connections.push(format!("{}-{}", small[a], cave));
+        if a != b {
+            connections.push(format!("{}-{}", cave, small[b]));
+        }
+    }
+
+    rng.shuffle(&mut connections);
+    join_lines(connections.into_iter())
+}
+
+/// `size` dots on paper that folds down to 40x6 in six folds.
+pub fn day13(rng: &mut Rng, size: usize) -> String {
+    let folds = ['x', 'y', 'x', 'y', 'x', 'y'];
+
+    // Unfold from the final size, each fold line in the middle of the paper
+    let (mut width, mut height) = (40, 6);
+    let mut lines = Vec::new();
+    for &axis in folds.iter().rev() {
+        if axis == 'x' {
+            lines.push(format!("fold along x={}", width));
+            width = 2 * width + 1;
+        } else {
+            lines.push(format!("fold along y={}", height));
+            height = 2 * height + 1;
+        }
+    }
+    lines.reverse();
+
+    let on_fold_line = |x: u64, y: u64| {
+        let (mut w, mut h) = (40, 6);
+        let mut crossed = false;
+        for _ in 0..3 {
+            crossed |= x == w || y == h;
+            w = 2 * w + 1;
+            h = 2 * h + 1;
+        }
+        crossed
+    };
+
+    let mut text = String::new();
+    let mut placed = 0;
+    while placed < size {
+        let (x, y) = (rng.range(0, width), rng.range(0, height));
+        if !on_fold_line(x, y) {
+            let _ = writeln!(text, "{},{}", x, y);
+            placed += 1;
+        }
+    }
+
+    text.push('\n');
+    text.push_str(&join_lines(lines.into_iter()));
+    text
+}
+
+/// A polymer template of `size` letters with a rule for every pair.
+pub fn day14(rng: &mut Rng, size: usize) -> String {
+    let letters = "BCFHKNOPSV".chars().collect::<Vec<char>>();
+
+    let mut text = (0..size).map(|_| rng.pick(&letters)).collect::<String>();
+    text.push_str("\n\n");
+    for &a in &letters {
+        for &b in &letters {
+            let _ = writeln!(text, "{}{} -> {}", a, b, rng.pick(&letters));
+        }
+    }
+
+    text
+}
+
+/// A `size` by `size` map of risk levels.
+pub fn day15(rng: &mut Rng, size: usize) -> String {
+    digit_grid(rng, size, 1, 10)
+}
+
+fn packet(rng: &mut Rng, budget: usize, depth: usize) -> Packet {
+    let version = rng.range(0, 8) as u8;
+
+    if budget <= 1 || depth >= day16::MAX_DEPTH {
+        return Packet {
+            header: Header { version, id: 4 },
+            body: PacketType::Literal(rng.range(0, 16) as i64),
+        };
+    }
+
+    // Products only of small literals, so the value can't overflow
+    let operator = rng.pick(&[
+        Operator::Sum,
+        Operator::Product,
+        Operator::Min,
+        Operator::Max,
+        Operator::GT,
+        Operator::LT,
+        Operator::EQ,
+    ]);
+    let count = match operator {
+        Operator::GT | Operator::LT | Operator::EQ => 2,
+        _ => rng.range(1, 5) as usize,
+    };
+
+    // Uneven shares of the budget give deep, lopsided trees
+    let mut budget = budget - 1;
+    let sub_packets = (0..count)
+        .map(|i| {
+            let share = if i + 1 == count {
+                budget
+            } else {
+                rng.range(0, budget as u64 + 1) as usize
+            };
+            budget -= share;
+
+            if operator == Operator::Product {
+                packet(rng, 1, depth + 1)
+            } else {
+                packet(rng, share, depth + 1)
+            }
+        })
+        .collect();
+
+    Packet {
+        header: Header {
+            version,
+            id: operator.type_id(),
+        },
+        body: PacketType::Operator(operator, sub_packets),
+    }
+}
+
+/// A transmission of about `size` packets.
+pub fn day16(rng: &mut Rng, size: usize) -> String {
+    let packet = packet(rng, size, 0);
+
+    // Bit lengths only have 15 bits, so large trees count their sub-packets
+    let by_count = rng.one_in(2) || day16::packet_to_binary(&packet, false).len() >= 1 << 15;
+    format!("{}\n", day16::packet_to_hex(&packet, by_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::DAYS;
+
+    #[test]
+    fn rng_is_reproducible() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+
+        let first = (0..5).map(|_| a.next_u64()).collect::<Vec<u64>>();
+        assert_eq!(first, (0..5).map(|_| b.next_u64()).collect::<Vec<u64>>());
+        assert_ne!(
+            first,
+            (0..5).map(|_| Rng::new(8).next_u64()).collect::<Vec<u64>>()
+        );
+        assert_eq!(Rng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn generated_inputs_solve() {
+        // Puzzle sized inputs from a few seeds, and one input several times larger
+        let cases = (0..5).map(|seed| (10, seed)).chain([(50, 0)]);
+
+        for day in DAYS {
+            for (size, seed) in cases.clone() {
+                // Days scaffolded since these were written have no generator yet
+                let input = match generate(day.number, size, seed) {
+                    Some(input) => input,
+                    None => continue,
+                };
+
+                assert_eq!(generate(day.number, size, seed).unwrap(), input);
+                if let Err(err) = day.solve(&input, None) {
+                    panic!(
+                        "day {} size {} seed {}: {}\n{}",
+                        day.number, size, seed, err, input
+                    );
+                }
+            }
+        }
+
+        assert!((1..=16).all(|day| generate(day, 10, 0).is_some()));
+        assert_eq!(generate(25, 10, 0), None);
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(generate(1, 1000, 1).unwrap().lines().count(), 1000);
+        assert_eq!(generate(5, 500, 1).unwrap().lines().count(), 500);
+        assert_eq!(generate(15, 100, 1).unwrap().lines().count(), 100);
+        assert_eq!(generate(4, 3, 1).unwrap().matches("\n\n").count(), 3);
+    }
+}
@@ -0,0 +1,315 @@
+use std::ops::{Index, IndexMut};
+
+use crate::error::{Error, Result};
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+fn offsets(
+    (x, y): (usize, usize),
+    (width, height): (usize, usize),
+    offsets: &'static [(isize, isize)],
+) -> impl Iterator<Item = (usize, usize)> {
+    offsets.iter().filter_map(move |&(dx, dy)| {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+
+        (x < width && y < height).then_some((x, y))
+    })
+}
+
+/// The up to 4 positions above, left, right and below `pos` inside a
+/// `width` x `height` area, in that order.
+pub fn neighbours4(
+    pos: (usize, usize),
+    (width, height): (usize, usize),
+) -> impl Iterator<Item = (usize, usize)> {
+    offsets(pos, (width, height), &ORTHOGONAL)
+}
+
+/// The up to 8 positions surrounding `pos` inside a `width` x `height` area,
+/// row by row.
+pub fn neighbours8(
+    pos: (usize, usize),
+    (width, height): (usize, usize),
+) -> impl Iterator<Item = (usize, usize)> {
+    offsets(pos, (width, height), &SURROUNDING)
+}
+
+/// A rectangular grid stored row by row. Positions are `(x, y)` with `(0, 0)` at
+/// the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from cells listed row by row.
+    ///
+    /// Panics if `width` is 0 or doesn't divide the number of cells.
+    pub fn new(data: Vec<T>, width: usize) -> Self {
+        assert!(
+            width > 0 && data.len().is_multiple_of(width),
+            "{} cells don't make rows of {}",
+            data.len(),
+            width
+        );
+
+        let height = data.len() / width;
+        Grid {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Parses a grid with one row per line, mapping every character with `cell`.
+    /// Rows must all be the same, non-zero, length. `expected` names a valid
+    /// character, such as `"digit"`, for errors.
+    pub fn parse_with<F>(input: &str, expected: &str, cell: F) -> Result<Self>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let first_row = input.lines().next().unwrap_or_default().trim();
+        let width = first_row.chars().count();
+        if width == 0 {
+            let reason = format!("expected a row of {}s", expected);
+            return Err(Error::parse(input, first_row, reason));
+        }
+
+        let mut data = Vec::with_capacity(input.len());
+
+        for line in input.lines().map(str::trim) {
+            if line.chars().count() != width {
+                let reason = format!("expected a row of {} {}s", width, expected);
+                return Err(Error::parse(input, line, reason));
+            }
+
+            for (i, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| {
+                    let reason = format!("expected a {}", expected);
+                    Error::parse(input, &line[i..i + c.len_utf8()], reason)
+                })?;
+                data.push(value);
+            }
+        }
+
+        Ok(Grid::new(data, width))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The position of the bottom-right cell.
+    pub fn bottom_right(&self) -> (usize, usize) {
+        (self.width - 1, self.height - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn index(&self, (x, y): (usize, usize)) -> usize {
+        (y * self.width) + x
+    }
+
+    pub fn coord(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        if self.contains(pos) {
+            self.data.get(self.index(pos))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        if self.contains(pos) {
+            let index = self.index(pos);
+            self.data.get_mut(index)
+        } else {
+            None
+        }
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.data.len()).map(move |i| (i % width, i / width))
+    }
+
+    /// The cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut()
+    }
+
+    /// The cells with their positions, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.data.iter())
+    }
+
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        neighbours4(pos, self.dimensions())
+    }
+
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        neighbours8(pos, self.dimensions())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.data.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} outside width {}", x, self.width);
+
+        self.data.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a map of single digits such as `"219\n398"`.
+    pub fn parse_digits(input: &str) -> Result<Self> {
+        Grid::parse_with(input, "digit", |c| c.to_digit(10).map(|digit| digit as u8))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        assert!(
+            self.contains(pos),
+            "{:?} outside {:?}",
+            pos,
+            self.dimensions()
+        );
+
+        &self.data[Grid::index(self, pos)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        assert!(
+            self.contains(pos),
+            "{:?} outside {:?}",
+            pos,
+            self.dimensions()
+        );
+
+        let index = Grid::index(self, pos);
+        &mut self.data[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_coord() {
+        let grid = Grid::new(vec![0; 100], 10);
+
+        assert_eq!(grid.index((5, 5)), 55);
+        assert_eq!(grid.coord(55), (5, 5));
+        assert_eq!(grid.dimensions(), (10, 10));
+    }
+
+    #[test]
+    fn parse_digits_test() {
+        let grid = Grid::parse_digits("219\n398\n").unwrap();
+
+        assert_eq!(grid.dimensions(), (3, 2));
+        assert_eq!(grid[(2, 0)], 9);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.row(1), &[3, 9, 8]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<u8>>(), vec![1, 9]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.bottom_right(), (2, 1));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Grid::parse_digits("219\n3x8\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 0 line 2 column 2: expected a digit (found \"x\")"
+        );
+
+        let err = Grid::parse_digits("219\n39\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 0 line 2 column 1: expected a row of 3 digits (found \"39\")"
+        );
+
+        assert!(Grid::parse_digits("").is_err());
+        assert!(Grid::parse_digits("\n12").is_err());
+    }
+
+    #[test]
+    fn neighbours_test() {
+        let grid = Grid::new(vec![0; 12], 4);
+
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((3, 2)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(neighbours4((3, 0), (20, 20)).count(), 3);
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Result,
+    store::{Fixture, Store},
+};
+
+/// Environment variable naming a directory of `dayN.txt` inputs to use instead of
+/// the real inputs in the fixture store.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// The path that means "read from stdin".
+pub const STDIN_PATH: &str = "-";
+
+fn default_path_from(var: Option<OsString>, day: u8) -> PathBuf {
+    match var {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join(format!("day{}.txt", day)),
+        _ => Store::local().path(&Fixture::real(day)),
+    }
+}
+
+/// Where the input for `day` lives by default: `dayN.txt` in `AOC_INPUT_DIR` if
+/// set, otherwise the real input in the crate's fixture store, so the runner works
+/// from any working directory.
+pub fn default_path(day: u8) -> PathBuf {
+    default_path_from(env::var_os(INPUT_DIR_VAR), day)
+}
+
+/// Reads a whole input from any reader.
+pub fn read<R: Read>(mut reader: R) -> Result<String> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    Ok(input)
+}
+
+/// Reads the input at `path`, or stdin if `path` is `-`.
+pub fn read_path<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+
+    if path == Path::new(STDIN_PATH) {
+        read(io::stdin().lock())
+    } else {
+        read(File::open(path)?)
+    }
+}
+
+/// Opens the input at `path`, or stdin if `path` is `-`, to read a line at a
+/// time.
+pub fn open_path<P: AsRef<Path>>(path: P) -> Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+
+    if path == Path::new(STDIN_PATH) {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_path_test() {
+        assert_eq!(
+            default_path_from(Some(OsString::from("/tmp/inputs")), 3),
+            PathBuf::from("/tmp/inputs/day3.txt")
+        );
+        assert!(default_path_from(None, 3).ends_with("input/2021/day3/input.txt"));
+        assert!(default_path_from(Some(OsString::new()), 3).ends_with("day3/input.txt"));
+    }
+
+    #[test]
+    fn read_test() {
+        assert_eq!(read("199\n200\n".as_bytes()).unwrap(), "199\n200\n");
+        assert!(read(&[0xff, 0xfe][..]).is_err());
+    }
+}
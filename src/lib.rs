@@ -0,0 +1,34 @@
+//! Advent of Code 2021 solutions. Every day is a public module with its parsers,
+//! solvers and a [`Solution`](solution::Solution) implementation, and
+//! [`registry`] lists them all for runners.
+
+pub mod answers;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod parallel;
+pub mod registry;
+pub mod render;
+pub mod report;
+pub mod scaffold;
+pub mod solution;
+pub mod store;
+pub mod submit;
+pub mod watch;
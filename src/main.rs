@@ -1,23 +1,692 @@
-#![allow(dead_code)]
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-fn main() {
-    day16::part1();
-    day16::part2();
+mod cli;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use advent_of_code_2021::{
+    day1::{self, Day1, SonarReport},
+    day2::{self, Aimed, Day2, Plain, SubmarineModel, Trajectory},
+    error::Error,
+    generate, input, parallel,
+    registry::{self, Day},
+    render,
+    report::{self, DayRun},
+    scaffold::Scaffold,
+    solution::{Answer, Solution, Solved},
+    store::{self, Fixture, Store},
+    submit::{self, Submission, Verdict},
+    watch,
+};
+use cli::{Command, Selection};
+
+/// The outcome of solving one day, with the line of input any error points at.
+type PathResult = Result<Solved, (Error, Option<String>)>;
+
+/// Reads and solves a day, returning any error with the line of input it points
+/// at. Days that can stream read the input a line at a time, and the others
+/// solve up to `jobs` parts at once.
+fn solve_path(day: &Day, part: Option<u8>, path: &Path, jobs: usize) -> PathResult {
+    if let Some(solve_stream) = day.solve_stream {
+        let mut reader = input::open_path(path).map_err(|err| (err, None))?;
+
+        return solve_stream(&mut reader, part).map_err(|err| {
+            // Read back as far as the error to show it, unless stdin is used up
+            let excerpt = Some(path)
+                .filter(|&path| path != Path::new(input::STDIN_PATH))
+                .and_then(|path| input::open_path(path).ok())
+                .and_then(|reader| err.excerpt_reader(reader));
+            (err, excerpt)
+        });
+    }
+
+    let input = input::read_path(path).map_err(|err| (err, None))?;
+    (day.solve_timed)(&input, part, jobs).map_err(|err| {
+        let excerpt = err.excerpt(&input);
+        (err, excerpt)
+    })
+}
+
+/// Prints the answers or error of a solved day, unless quiet.
+fn report_day(day: &Day, path: &Path, result: PathResult, quiet: bool) -> DayRun {
+    let outcome = match result {
+        Ok(solved) => {
+            if !quiet {
+                for (part, answer) in &solved.answers {
+                    match answer {
+                        Answer::Grid(grid) => {
+                            println!("Day {} part {}:\n{}", day.number, part, grid)
+                        }
+                        answer => println!("Day {} part {}: {}", day.number, part, answer),
+                    }
+                }
+            }
+            Ok(solved)
+        }
+        Err((err @ Error::Io(_), _)) => {
+            eprintln!(
+                "Day {}: could not read {}: {}",
+                day.number,
+                path.display(),
+                err
+            );
+            Err(err)
+        }
+        Err((err, excerpt)) => {
+            eprintln!("error: {}", err);
+            if let Some(excerpt) = excerpt {
+                eprintln!("{}", excerpt);
+            }
+            Err(err)
+        }
+    };
+
+    DayRun {
+        day: day.number,
+        outcome,
+    }
+}
+
+fn run(
+    selection: Selection,
+    part: Option<u8>,
+    input: Option<String>,
+    fixture: Option<String>,
+    json: bool,
+    jobs: usize,
+) -> ExitCode {
+    let days = match selection {
+        Selection::All => registry::DAYS
+            .iter()
+            .map(|day| (day, day.default_input()))
+            .collect::<Vec<(&Day, PathBuf)>>(),
+        Selection::Day(number) => {
+            let day = match registry::find(number) {
+                Some(day) => day,
+                None => {
+                    eprintln!("Unknown day {}", number);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let path = match (input, fixture) {
+                (Some(input), _) => PathBuf::from(input),
+                (_, Some(name)) => Store::local().path(&Fixture::named(number, name)),
+                _ => day.default_input(),
+            };
+            vec![(day, path)]
+        }
+    };
+
+    // Solve everything first so the output stays in day order. The jobs go to
+    // the days if there are several, so each solves its parts one at a time
+    let part_jobs = if days.len() > 1 { 1 } else { jobs };
+    let results = parallel::map(&days, jobs, |(day, path)| {
+        solve_path(day, part, path, part_jobs)
+    });
+    let runs = days
+        .iter()
+        .zip(results)
+        .map(|((day, path), result)| report_day(day, path, result, json))
+        .collect::<Vec<DayRun>>();
+
+    if json {
+        println!("{}", report::json(&runs));
+    } else {
+        println!("\n{}", report::table(&runs));
+    }
+
+    if runs.iter().any(|run| run.outcome.is_err()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn stored_fixtures(store: &Store, day: Option<u8>) -> Option<Vec<Fixture>> {
+    let fixtures = match day {
+        Some(day) => store.list_day(store::YEAR, day),
+        None => store.list(),
+    };
+
+    match fixtures {
+        Ok(fixtures) => Some(fixtures),
+        Err(err) => {
+            eprintln!(
+                "Could not list fixtures in {}: {}",
+                store.root().display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+fn list_fixtures(day: Option<u8>) -> ExitCode {
+    let store = Store::local();
+    let fixtures = match stored_fixtures(&store, day) {
+        Some(fixtures) => fixtures,
+        None => return ExitCode::FAILURE,
+    };
+
+    for fixture in fixtures {
+        let parts = store
+            .expected(&fixture)
+            .map(|expected| expected.len())
+            .unwrap_or(0);
+        println!("{:<24} {} known answers", fixture.to_string(), parts);
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Answers given on the command line: numbers if they parse as one, text otherwise.
+fn parse_answer(value: String) -> Answer {
+    value
+        .parse()
+        .map(Answer::Number)
+        .unwrap_or(Answer::Text(value))
+}
+
+fn add_fixture(
+    day: u8,
+    name: String,
+    input: Option<String>,
+    answers: Vec<(u8, String)>,
+) -> ExitCode {
+    let path = input.unwrap_or_else(|| input::STDIN_PATH.to_string());
+    let text = match input::read_path(&path) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("Could not read {}: {}", path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let fixture = Fixture::named(day, name);
+    let answers = answers
+        .into_iter()
+        .map(|(part, value)| (part, parse_answer(value)))
+        .collect::<Vec<(u8, Answer)>>();
+
+    match Store::local().add(&fixture, &text, &answers) {
+        Ok(()) => {
+            println!("Added {}", fixture);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Could not add {}: {}", fixture, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn validate_fixtures(day: Option<u8>) -> ExitCode {
+    let store = Store::local();
+    let fixtures = match stored_fixtures(&store, day) {
+        Some(fixtures) => fixtures,
+        None => return ExitCode::FAILURE,
+    };
+
+    let mut all_passed = true;
+    for fixture in fixtures {
+        let validation = match store.validate(&fixture) {
+            Ok(validation) => validation,
+            Err(err) => {
+                println!("{:<24} error: {}", fixture.to_string(), err);
+                all_passed = false;
+                continue;
+            }
+        };
+
+        all_passed &= validation.passed();
+        for check in validation.parts {
+            let status = match &check.expected {
+                Some(expected) if *expected == check.actual => "ok".to_string(),
+                Some(expected) => format!("FAILED, expected {}", expected),
+                None => "no known answer".to_string(),
+            };
+            let actual = match &check.actual {
+                Answer::Grid(_) => "grid".to_string(),
+                actual => actual.to_string(),
+            };
+            println!(
+                "{:<24} part {}: {} ({})",
+                fixture.to_string(),
+                check.part,
+                status,
+                actual
+            );
+        }
+    }
+
+    if all_passed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn generate_input(day: u8, size: usize, seed: u64) -> ExitCode {
+    match generate::generate(day, size, seed) {
+        Some(input) => {
+            print!("{}", input);
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("No generator for day {}", day);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The answer to send for a part: the one given, or the part solved from the
+/// input, which defaults to the real one.
+fn answer_to_submit(
+    day: &Day,
+    part: u8,
+    input: Option<String>,
+    answer: Option<String>,
+) -> Option<String> {
+    if answer.is_some() {
+        return answer;
+    }
+
+    let path = input.map_or_else(|| day.default_input(), PathBuf::from);
+    let solved = report_day(day, &path, solve_path(day, Some(part), &path, 1), true)
+        .outcome
+        .ok()?;
+
+    match solved
+        .answers
+        .into_iter()
+        .find(|&(number, _)| number == part)
+    {
+        Some((_, Answer::Grid(grid))) => {
+            println!("{}", grid);
+            eprintln!("Read the letters above and send them with --answer");
+            None
+        }
+        Some((_, answer)) => Some(answer.to_string()),
+        None => {
+            eprintln!("Day {} has no part {}", day.number, part);
+            None
+        }
+    }
+}
+
+fn submit_answer(
+    day: u8,
+    part: u8,
+    input: Option<String>,
+    answer: Option<String>,
+    url: Option<String>,
+) -> ExitCode {
+    let day = match registry::find(day) {
+        Some(day) => day,
+        None => {
+            eprintln!("Unknown day {}", day);
+            return ExitCode::FAILURE;
+        }
+    };
+    let answer = match answer_to_submit(day, part, input, answer) {
+        Some(answer) => answer,
+        None => return ExitCode::FAILURE,
+    };
+
+    let session = match std::env::var("AOC_SESSION") {
+        Ok(session) if !session.is_empty() => session,
+        _ => {
+            eprintln!("Set AOC_SESSION to the session cookie to submit answers");
+            return ExitCode::FAILURE;
+        }
+    };
+    let url = url
+        .or_else(|| std::env::var("AOC_URL").ok())
+        .unwrap_or_else(|| submit::DEFAULT_URL.to_string());
+
+    let store = Store::local();
+    let log_path = store
+        .day_dir(store::YEAR, day.number)
+        .join(submit::LOG_FILE);
+    let mut log = match submit::Log::open(&log_path) {
+        Ok(log) => log,
+        Err(err) => {
+            eprintln!("Could not read {}: {}", log_path.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let client = submit::Client::new(&url, &session);
+    match submit::submit(&client, &mut log, (store::YEAR, day.number, part), &answer) {
+        Ok(Submission::Refused(reason)) => {
+            eprintln!("Not sending {}: {}", answer, reason);
+            ExitCode::FAILURE
+        }
+        Ok(Submission::Sent(response)) => {
+            println!(
+                "Day {} part {}: {} is {}",
+                day.number, part, answer, response.verdict
+            );
+            println!("{}", response.message);
+
+            if response.verdict == Verdict::Right {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(err) => {
+            eprintln!("Could not submit {}: {}", answer, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn write_file(path: &str, write: impl FnOnce(BufWriter<File>) -> Result<(), Error>) -> bool {
+    match File::create(path)
+        .map_err(Error::from)
+        .and_then(|file| write(BufWriter::new(file)))
+    {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("Could not write {}: {}", path, err);
+            false
+        }
+    }
+}
+
+/// Reads the given input, or the day's default one, printing why if it can't.
+fn read_day_input(day: u8, input: Option<String>) -> Option<String> {
+    let path = match (input, registry::find(day)) {
+        (Some(input), _) => PathBuf::from(input),
+        (None, Some(day)) => day.default_input(),
+        (None, None) => {
+            eprintln!("Unknown day {}", day);
+            return None;
+        }
+    };
+
+    match input::read_path(&path) {
+        Ok(text) => Some(text),
+        Err(err) => {
+            eprintln!("Could not read {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn render_day(day: u8, out: String, input: Option<String>, scale: usize, frames: bool) -> ExitCode {
+    let text = match read_day_input(day, input) {
+        Some(text) => text,
+        None => return ExitCode::FAILURE,
+    };
+
+    let drawn = match render::frames(day, &text) {
+        Some(Ok(drawn)) => drawn,
+        Some(Err(err)) => {
+            eprintln!("error: {}", err);
+            if let Some(excerpt) = err.excerpt(&text) {
+                eprintln!("{}", excerpt);
+            }
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("Day {} has nothing to draw", day);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let written = if out.ends_with(".gif") {
+        write_file(&out, |file| {
+            render::write_gif(&drawn, scale, render::FRAME_DELAY, file)
+        })
+    } else if frames {
+        let stem = out.trim_end_matches(".png");
+        let digits = drawn.len().to_string().len();
+
+        drawn.iter().enumerate().all(|(i, frame)| {
+            let path = format!("{}-{:0digits$}.png", stem, i, digits = digits);
+            write_file(&path, |file| render::write_png(frame, scale, file))
+        })
+    } else {
+        let last = drawn.last().expect("every day draws at least one frame");
+        write_file(&out, |file| render::write_png(last, scale, file))
+    };
+
+    if written {
+        let plural = if drawn.len() == 1 { "" } else { "s" };
+        println!("Drew {} frame{} of day {}", drawn.len(), plural, day);
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn watch_day(day: u8, input: Option<String>) -> ExitCode {
+    let text = match read_day_input(day, input) {
+        Some(text) => text,
+        None => return ExitCode::FAILURE,
+    };
+
+    let simulation = match watch::simulation(day, &text) {
+        Some(Ok(simulation)) => simulation,
+        Some(Err(err)) => {
+            eprintln!("error: {}", err);
+            if let Some(excerpt) = err.excerpt(&text) {
+                eprintln!("{}", excerpt);
+            }
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("Day {} has no simulation to watch", day);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match watch::watch(simulation) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Could not use the terminal: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn new_day(day: u8) -> ExitCode {
+    match Scaffold::local().create(day) {
+        Ok(changed) => {
+            for path in changed {
+                println!("Wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Could not add day {}: {}", day, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn sonar_report(input: Option<String>, windows: Vec<usize>, csv: Option<String>) -> ExitCode {
+    let text = match read_day_input(Day1::DAY, input) {
+        Some(text) => text,
+        None => return ExitCode::FAILURE,
+    };
+
+    let report = Day1::parse(&text).and_then(|depths| {
+        let report = SonarReport::new(&depths, &windows)?;
+        Ok((depths, report))
+    });
+    let (depths, report) = match report.map_err(|err| err.in_day(Day1::DAY)) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            if let Some(excerpt) = err.excerpt(&text) {
+                eprintln!("{}", excerpt);
+            }
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match csv.as_deref() {
+        Some(input::STDIN_PATH) => {
+            return match day1::write_window_sums(&depths, &windows, io::stdout().lock()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => {
+                    eprintln!("Could not write the window sums: {}", err);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(path) => {
+            if !write_file(path, |file| {
+                day1::write_window_sums(&depths, &windows, file)
+            }) {
+                return ExitCode::FAILURE;
+            }
+            println!("Wrote the window sums to {}\n", path);
+        }
+        None => (),
+    }
+
+    print!("{}", report);
+    ExitCode::SUCCESS
+}
+
+/// Records the course `text` steers `start` on, printing why if it can't.
+fn record_course<M>(start: M, text: &str, lenient: bool) -> Option<Trajectory<M>>
+where
+    M: SubmarineModel + Clone,
+{
+    let trajectory = Trajectory::new(start);
+    let course = if lenient {
+        day2::execute_lenient(trajectory, text.lines()).map(|(trajectory, warnings)| {
+            for warning in warnings {
+                eprintln!("skipped: {}", warning.in_day(Day2::DAY));
+            }
+            trajectory
+        })
+    } else {
+        Day2::parse(text).and_then(|instructions| day2::execute(trajectory, instructions))
+    };
+
+    match course {
+        Ok(trajectory) => Some(trajectory),
+        Err(err) => {
+            let err = err.in_day(Day2::DAY);
+            eprintln!("error: {}", err);
+            if let Some(excerpt) = err.excerpt(text) {
+                eprintln!("{}", excerpt);
+            }
+            None
+        }
+    }
+}
+
+/// Writes the trajectory as CSV or SVG, going by the file's extension.
+fn write_course<M>(trajectory: &Trajectory<M>, out: &str) -> bool
+where
+    M: SubmarineModel + Clone,
+{
+    let written = write_file(out, |file| {
+        if out.ends_with(".svg") {
+            trajectory.write_svg(file)
+        } else {
+            trajectory.write_csv(file)
+        }
+    });
+    if written {
+        let (horizontal, depth) = trajectory.position();
+        println!(
+            "Wrote {} steps to {}, ending at {} across and {} down",
+            trajectory.states().len() - 1,
+            out,
+            horizontal,
+            depth
+        );
+    }
+
+    written
+}
+
+fn course(out: String, input: Option<String>, part: u8, lenient: bool) -> ExitCode {
+    let text = match read_day_input(Day2::DAY, input) {
+        Some(text) => text,
+        None => return ExitCode::FAILURE,
+    };
+
+    let written = match part {
+        1 => record_course(Plain::default(), &text, lenient)
+            .is_some_and(|trajectory| write_course(&trajectory, &out)),
+        _ => record_course(Aimed::default(), &text, lenient)
+            .is_some_and(|trajectory| write_course(&trajectory, &out)),
+    };
+
+    if written {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn main() -> ExitCode {
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Run {
+            selection,
+            part,
+            input,
+            fixture,
+            json,
+            jobs,
+        } => run(selection, part, input, fixture, json, jobs),
+        Command::ListFixtures { day } => list_fixtures(day),
+        Command::AddFixture {
+            day,
+            name,
+            input,
+            answers,
+        } => add_fixture(day, name, input, answers),
+        Command::ValidateFixtures { day } => validate_fixtures(day),
+        Command::Generate { day, size, seed } => generate_input(day, size, seed),
+        Command::Submit {
+            day,
+            part,
+            input,
+            answer,
+            url,
+        } => submit_answer(day, part, input, answer, url),
+        Command::Render {
+            day,
+            out,
+            input,
+            scale,
+            frames,
+        } => render_day(day, out, input, scale, frames),
+        Command::Watch { day, input } => watch_day(day, input),
+        Command::New { day } => new_day(day),
+        Command::Sonar {
+            input,
+            windows,
+            csv,
+        } => sonar_report(input, windows, csv),
+        Command::Course {
+            out,
+            input,
+            part,
+            lenient,
+        } => course(out, input, part, lenient),
+    }
 }
@@ -0,0 +1,84 @@
+//! Runs independent jobs on a few threads, keeping the results in order.
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/// Applies `f` to every item using up to `jobs` threads, and returns the results
+/// in the order of `items`. With one job, or one item, everything runs on the
+/// calling thread.
+pub fn map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = jobs.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    // Each thread takes the next unclaimed item until none are left
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(i) {
+                            Some(item) => done.push((i, f(item))),
+                            None => return done,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect::<Vec<(usize, R)>>()
+    });
+
+    results.sort_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn keeps_order() {
+        let items = (0..20).collect::<Vec<u64>>();
+
+        for jobs in [0, 1, 3, 50] {
+            let squares = map(&items, jobs, |&n| {
+                // Later items finish first
+                thread::sleep(Duration::from_millis(20 - n));
+                n * n
+            });
+
+            assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<u64>>());
+        }
+    }
+
+    #[test]
+    fn uses_threads() {
+        let items = [(); 4];
+        let ids = map(&items, 4, |_| {
+            thread::sleep(Duration::from_millis(20));
+            thread::current().id()
+        });
+
+        assert!(ids.iter().any(|&id| id != thread::current().id()));
+    }
+}
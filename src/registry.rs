@@ -0,0 +1,124 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::PathBuf,
+};
+
+use crate::{
+    error::Result,
+    input,
+    solution::{self, Answers, Solution, Solved},
+};
+
+/// Solves a day's input read from a stream, a line at a time.
+pub type SolveStream = fn(&mut dyn BufRead, Option<u8>) -> Result<Solved>;
+
+pub struct Day {
+    pub number: u8,
+    /// Solves the input with up to the given number of parts at once.
+    pub solve_timed: fn(&str, Option<u8>, usize) -> Result<Solved>,
+    pub check_input: fn(&str) -> Result<()>,
+    /// Set for days that can solve their input a line at a time.
+    pub solve_stream: Option<SolveStream>,
+}
+
+impl Day {
+    pub fn default_input(&self) -> PathBuf {
+        input::default_path(self.number)
+    }
+
+    /// Solves the selected part, or both when `part` is `None`.
+    pub fn solve(&self, input: &str, part: Option<u8>) -> Result<Answers> {
+        (self.solve_timed)(input, part, 1).map(|solved| solved.answers)
+    }
+
+    /// Solves the selected part, or both, reading the input from `reader`. Days
+    /// that can stream read it a line at a time, the rest read it all first.
+    pub fn solve_reader<R: Read>(&self, reader: R, part: Option<u8>) -> Result<Answers> {
+        match self.solve_stream {
+            Some(solve_stream) => {
+                solve_stream(&mut BufReader::new(reader), part).map(|solved| solved.answers)
+            }
+            None => self.solve(&input::read(reader)?, part),
+        }
+    }
+}
+
+macro_rules! day {
+    ($solution:path) => {
+        Day {
+            number: <$solution as Solution>::DAY,
+            solve_timed: solution::solve_timed_jobs::<$solution>,
+            check_input: solution::check_input::<$solution>,
+            solve_stream: None,
+        }
+    };
+    ($solution:path, streaming) => {
+        Day {
+            solve_stream: Some(solution::solve_stream::<$solution>),
+            ..day!($solution)
+        }
+    };
+}
+
+/// Every day in order. `aoc new` adds new days here.
+pub const DAYS: &[Day] = &[
+    day!(crate::day1::Day1, streaming),
+    day!(crate::day2::Day2),
+    day!(crate::day3::Day3, streaming),
+    day!(crate::day4::Day4),
+    day!(crate::day5::Day5, streaming),
+    day!(crate::day6::Day6),
+    day!(crate::day7::Day7),
+    day!(crate::day8::Day8),
+    day!(crate::day9::Day9),
+    day!(crate::day10::Day10),
+    day!(crate::day11::Day11),
+    day!(crate::day12::Day12),
+    day!(crate::day13::Day13),
+    day!(crate::day14::Day14),
+    day!(crate::day15::Day15),
+    day!(crate::day16::Day16),
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_in_order() {
+        let numbers = DAYS.iter().map(|day| day.number).collect::<Vec<u8>>();
+
+        assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(numbers[..16], (1..=16).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn find_test() {
+        assert_eq!(find(5).map(|day| day.number), Some(5));
+        assert!(find(0).is_none());
+        assert!(find(25).is_none());
+    }
+
+    #[test]
+    fn solve_reader_test() {
+        let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        let answers = find(1).unwrap().solve_reader(input.as_bytes(), Some(1));
+
+        assert_eq!(answers.unwrap(), vec![(1, 7.into())]);
+    }
+
+    #[test]
+    fn streaming_days() {
+        let streaming = DAYS
+            .iter()
+            .filter(|day| day.solve_stream.is_some())
+            .map(|day| day.number)
+            .collect::<Vec<u8>>();
+
+        assert_eq!(streaming, vec![1, 3, 5]);
+    }
+}
@@ -0,0 +1,275 @@
+//! Pictures of the grid puzzles. Days that implement [`Render`] draw their
+//! states as frames of coloured cells, which are written out as PNG images or
+//! as an animated GIF with one frame per step.
+
+use std::{collections::HashMap, io, io::Write};
+
+use crate::{
+    day11::Day11,
+    day13::Day13,
+    day15::Day15,
+    day9::Day9,
+    error::{Error, Result},
+    grid::Grid,
+    solution::Solution,
+};
+
+pub type Rgb = [u8; 3];
+
+/// One picture, with a colour for every cell of a grid.
+pub type Frame = Grid<Rgb>;
+
+pub const BACKGROUND: Rgb = [15, 15, 35];
+
+/// Colours that are easy to tell apart, for labelling regions.
+pub const LABELS: [Rgb; 8] = [
+    [230, 25, 75],
+    [60, 180, 75],
+    [255, 225, 25],
+    [67, 99, 216],
+    [245, 130, 49],
+    [145, 30, 180],
+    [66, 212, 244],
+    [240, 50, 230],
+];
+
+/// How many pixels wide each cell is unless told otherwise.
+pub const DEFAULT_SCALE: usize = 4;
+
+/// How long each frame of an animation shows, in hundredths of a second.
+pub const FRAME_DELAY: u16 = 10;
+
+/// A solution that can draw its input, once or once per simulation step.
+pub trait Render: Solution {
+    fn frames(input: &Self::Input) -> Result<Vec<Frame>>;
+}
+
+/// Parses `input` and draws it, tagging errors with the solution's day.
+pub fn render<R: Render>(input: &str) -> Result<Vec<Frame>> {
+    let input = R::parse(input).map_err(|err| err.in_day(R::DAY))?;
+
+    R::frames(&input).map_err(|err| err.in_day(R::DAY))
+}
+
+/// The frames for `day`'s input, or `None` if that day can't be drawn.
+pub fn frames(day: u8, input: &str) -> Option<Result<Vec<Frame>>> {
+    Some(match day {
+        9 => render::<Day9>(input),
+        11 => render::<Day11>(input),
+        13 => render::<Day13>(input),
+        15 => render::<Day15>(input),
+        _ => return None,
+    })
+}
+
+/// The colour `t` of the way from `from` to `to`, for `t` from 0 to 1.
+pub fn blend(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+    [
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+    ]
+}
+
+/// A frame the size of `grid`, colouring each cell with `colour`.
+pub fn draw<T, F>(grid: &Grid<T>, colour: F) -> Frame
+where
+    F: Fn((usize, usize), &T) -> Rgb,
+{
+    let data = grid.cells().map(|(pos, cell)| colour(pos, cell)).collect();
+
+    Grid::new(data, grid.width())
+}
+
+fn image_error(reason: impl Into<String>) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, reason.into()))
+}
+
+/// The RGB bytes of `frame` with every cell `scale` pixels square, placed at the
+/// top left of a `width` x `height` pixel image filled with the background.
+fn pixels(frame: &Frame, scale: usize, (width, height): (usize, usize)) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width * height * 3);
+
+    for y in 0..height {
+        for x in 0..width {
+            let colour = frame.get((x / scale, y / scale)).unwrap_or(&BACKGROUND);
+            pixels.extend_from_slice(colour);
+        }
+    }
+
+    pixels
+}
+
+fn scaled(frame: &Frame, scale: usize) -> (usize, usize) {
+    (frame.width() * scale, frame.height() * scale)
+}
+
+/// Writes `frame` as a PNG with every cell `scale` pixels square.
+pub fn write_png(frame: &Frame, scale: usize, writer: impl Write) -> Result<()> {
+    let scale = scale.max(1);
+    let (width, height) = scaled(frame, scale);
+    let size = |pixels: usize| {
+        u32::try_from(pixels).map_err(|_| image_error("the image is too large for a PNG"))
+    };
+
+    let mut encoder = png::Encoder::new(writer, size(width)?, size(height)?);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io::Error::from)?;
+    writer
+        .write_image_data(&pixels(frame, scale, (width, height)))
+        .map_err(io::Error::from)?;
+
+    Ok(())
+}
+
+/// Palette indices and the palette for `pixels`, if they use at most 256 colours.
+fn indexed(pixels: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut colours = HashMap::new();
+    let mut palette = Vec::new();
+    let mut indices = Vec::with_capacity(pixels.len() / 3);
+
+    for colour in pixels.chunks(3) {
+        let next = colours.len();
+        let index = *colours.entry(colour).or_insert(next);
+        if index == next {
+            palette.extend_from_slice(colour);
+        }
+        indices.push(u8::try_from(index).ok()?);
+    }
+
+    Some((indices, palette))
+}
+
+/// Writes `frames` as a looping GIF with every cell `scale` pixels square. Each
+/// frame shows for `delay` hundredths of a second, and frames smaller than the
+/// largest are padded with the background colour.
+pub fn write_gif(frames: &[Frame], scale: usize, delay: u16, writer: impl Write) -> Result<()> {
+    let scale = scale.max(1);
+    let width = frames.iter().map(|frame| scaled(frame, scale).0).max();
+    let height = frames.iter().map(|frame| scaled(frame, scale).1).max();
+
+    let (width, height) = match (width, height) {
+        (Some(width), Some(height)) => (width, height),
+        _ => return Err(image_error("there are no frames to write")),
+    };
+    let size = |pixels: usize| {
+        u16::try_from(pixels).map_err(|_| image_error("the image is too large for a GIF"))
+    };
+    let gif_error = |err: gif::EncodingError| Error::Io(io::Error::other(err));
+
+    let mut encoder =
+        gif::Encoder::new(writer, size(width)?, size(height)?, &[]).map_err(gif_error)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(gif_error)?;
+
+    for frame in frames {
+        let pixels = pixels(frame, scale, (width, height));
+        let (width, height) = (size(width)?, size(height)?);
+
+        let mut frame = match indexed(&pixels) {
+            Some((indices, palette)) => {
+                gif::Frame::from_palette_pixels(width, height, indices, palette, None)
+            }
+            None => gif::Frame::from_rgb_speed(width, height, &pixels, 10),
+        };
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(gif_error)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::fixture;
+
+    const WHITE: Rgb = [255, 255, 255];
+
+    fn checkerboard(width: usize, height: usize) -> Frame {
+        let cells = Grid::new(vec![(); width * height], width);
+        draw(
+            &cells,
+            |(x, y), _| if (x + y) % 2 == 0 { WHITE } else { BACKGROUND },
+        )
+    }
+
+    #[test]
+    fn blend_test() {
+        assert_eq!(blend(BACKGROUND, WHITE, 0.0), BACKGROUND);
+        assert_eq!(blend(BACKGROUND, WHITE, 1.0), WHITE);
+        assert_eq!(blend([0, 100, 200], [100, 100, 0], 0.5), [50, 100, 100]);
+        assert_eq!(blend([0, 0, 0], WHITE, 2.0), WHITE);
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let mut image = Vec::new();
+        write_png(&checkerboard(3, 2), 2, &mut image).unwrap();
+
+        let mut reader = png::Decoder::new(image.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!((info.width, info.height), (6, 4));
+        assert_eq!(pixels[..3], WHITE);
+        assert_eq!(pixels[3..6], WHITE);
+        assert_eq!(pixels[6..9], BACKGROUND);
+        // Second row of pixels is still the first row of cells
+        assert_eq!(pixels[18..21], WHITE);
+    }
+
+    #[test]
+    fn gif_frames() {
+        let mut image = Vec::new();
+        let frames = [checkerboard(2, 2), checkerboard(4, 1)];
+        write_gif(&frames, 3, FRAME_DELAY, &mut image).unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(image.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (12, 6));
+
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, FRAME_DELAY);
+            assert_eq!(frame.buffer[..4], [255, 255, 255, 255]);
+            count += 1;
+        }
+        assert_eq!(count, 2);
+
+        assert!(write_gif(&[], 1, FRAME_DELAY, Vec::new()).is_err());
+        assert!(write_gif(&[checkerboard(70_000, 1)], 1, FRAME_DELAY, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn many_colours() {
+        let cells = Grid::new(vec![(); 40 * 40], 40);
+        let frame = draw(&cells, |(x, y), _| [x as u8 * 6, y as u8 * 6, 0]);
+
+        assert!(indexed(&pixels(&frame, 1, (40, 40))).is_none());
+        assert!(write_gif(&[frame], 1, FRAME_DELAY, Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn day_frames() {
+        for day in 1..=16 {
+            let frames = frames(day, &fixture(day, "example"));
+
+            match day {
+                9 | 15 => assert_eq!(frames.unwrap().unwrap().len(), 1),
+                11 => assert_eq!(frames.unwrap().unwrap().len(), 196),
+                13 => assert_eq!(frames.unwrap().unwrap().len(), 3),
+                _ => assert!(frames.is_none()),
+            }
+        }
+
+        assert!(frames(9, "12\n3").unwrap().is_err());
+    }
+}
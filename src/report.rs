@@ -0,0 +1,199 @@
+use std::{fmt::Write, time::Duration};
+
+use crate::{error::Result, solution::Solved};
+
+/// The outcome of running one day.
+pub struct DayRun {
+    pub day: u8,
+    pub outcome: Result<Solved>,
+}
+
+/// A duration in the largest unit that keeps it above 1, such as `"12.3µs"`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+fn time_cell(time: Option<Duration>) -> String {
+    time.map_or_else(|| "-".to_string(), format_duration)
+}
+
+/// An aligned table of each day's parse, part 1, part 2 and total times, with
+/// the total of all successful days on the last line.
+pub fn table(runs: &[DayRun]) -> String {
+    let mut table = format!(
+        "{:>5} {:>10} {:>10} {:>10} {:>10}\n",
+        "Day", "Parse", "Part 1", "Part 2", "Total"
+    );
+    let mut total = Duration::ZERO;
+
+    for run in runs {
+        match &run.outcome {
+            Ok(solved) => {
+                let timings = &solved.timings;
+                total += timings.total();
+
+                let _ = writeln!(
+                    table,
+                    "{:>5} {:>10} {:>10} {:>10} {:>10}",
+                    run.day,
+                    format_duration(timings.parse),
+                    time_cell(timings.part(1)),
+                    time_cell(timings.part(2)),
+                    format_duration(timings.total()),
+                );
+            }
+            Err(_) => {
+                let _ = writeln!(table, "{:>5} {:>10}", run.day, "failed");
+            }
+        }
+    }
+
+    let _ = writeln!(table, "{:<5} {:>43}", "Total", format_duration(total));
+    table
+}
+
+/// `s` as a quoted JSON string.
+pub fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn json_day(run: &DayRun) -> String {
+    let solved = match &run.outcome {
+        Ok(solved) => solved,
+        Err(err) => {
+            return format!(
+                "{{\"day\":{},\"error\":{}}}",
+                run.day,
+                json_string(&err.to_string())
+            )
+        }
+    };
+
+    let parts = solved
+        .answers
+        .iter()
+        .map(|(part, answer)| {
+            let nanos = solved.timings.part(*part).unwrap_or_default().as_nanos();
+            format!(
+                "{{\"part\":{},\"answer\":{},\"time_ns\":{}}}",
+                part,
+                json_string(&answer.to_string()),
+                nanos
+            )
+        })
+        .collect::<Vec<String>>();
+
+    format!(
+        "{{\"day\":{},\"parse_ns\":{},\"parts\":[{}],\"total_ns\":{}}}",
+        run.day,
+        solved.timings.parse.as_nanos(),
+        parts.join(","),
+        solved.timings.total().as_nanos()
+    )
+}
+
+/// The same data as [`table`] as a single JSON object. Times are in nanoseconds
+/// and answers are strings.
+pub fn json(runs: &[DayRun]) -> String {
+    let days = runs.iter().map(json_day).collect::<Vec<String>>();
+    let total: Duration = runs
+        .iter()
+        .filter_map(|run| run.outcome.as_ref().ok())
+        .map(|solved| solved.timings.total())
+        .sum();
+
+    format!(
+        "{{\"days\":[{}],\"total_ns\":{}}}",
+        days.join(","),
+        total.as_nanos()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, solution::Timings};
+
+    fn runs() -> Vec<DayRun> {
+        vec![
+            DayRun {
+                day: 1,
+                outcome: Ok(Solved {
+                    answers: vec![(1, 7.into()), (2, "#.\n.#".into())],
+                    timings: Timings {
+                        parse: Duration::from_micros(12),
+                        parts: vec![
+                            (1, Duration::from_nanos(500)),
+                            (2, Duration::from_millis(3)),
+                        ],
+                    },
+                }),
+            },
+            DayRun {
+                day: 2,
+                outcome: Err(Error::unsolvable("no answer").in_day(2)),
+            },
+        ]
+    }
+
+    #[test]
+    fn format_duration_test() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_340)), "12.3µs");
+        assert_eq!(format_duration(Duration::from_micros(1_500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_millis(2_000)), "2.00s");
+    }
+
+    #[test]
+    fn table_test() {
+        assert_eq!(
+            table(&runs()),
+            "  Day      Parse     Part 1     Part 2      Total
+    1     12.0µs      500ns     3.00ms     3.01ms
+    2     failed
+Total                                      3.01ms
+"
+        );
+    }
+
+    #[test]
+    fn json_test() {
+        assert_eq!(
+            json(&runs()),
+            concat!(
+                r#"{"days":[{"day":1,"parse_ns":12000,"parts":["#,
+                r#"{"part":1,"answer":"7","time_ns":500},"#,
+                r##"{"part":2,"answer":"#.\n.#","time_ns":3000000}],"total_ns":3012500},"##,
+                r#"{"day":2,"error":"day 2: no answer"}],"total_ns":3012500}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Starting a new day: a module from a template, an empty example fixture to
+//! fill in, and the lines in `lib.rs` and the registry that make the runner
+//! find it, so neither list is edited by hand.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::{Error, Result},
+    store::{Fixture, Store},
+};
+
+/// The last day of the puzzle calendar.
+pub const LAST_DAY: u8 = 25;
+
+const DAYS_START: &str = "pub const DAYS: &[Day] = &[";
+const DAYS_END: &str = "];";
+
+fn invalid(reason: String) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, reason))
+}
+
+/// The source of a new day's module, solving both parts with placeholder
+/// answers so everything builds and passes until it is filled in. It is the
+/// template day, renumbered.
+pub fn module_source(day: u8) -> String {
+    TEMPLATE
+        .replace("Day0", &format!("Day{}", day))
+        .replace("day0", &format!("day{}", day))
+        .replace("DAY: u8 = 0", &format!("DAY: u8 = {}", day))
+}
+
+const TEMPLATE: &str = include_str!("scaffold/template.rs");
+
+#[cfg(test)]
+mod template;
+
+/// `lib` with `pub mod dayN;` added to its sorted block of module declarations.
+pub fn declare_module(lib: &str, day: u8) -> Result<String> {
+    let mut lines = lib.lines().collect::<Vec<&str>>();
+    let is_mod = |line: &&str| line.starts_with("pub mod ");
+
+    let first = lines.iter().position(is_mod);
+    let last = lines.iter().rposition(is_mod);
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) if lines[first..=last].iter().all(is_mod) => (first, last),
+        _ => return Err(invalid("expected one block of 'pub mod' lines".to_string())),
+    };
+
+    let declaration = format!("pub mod day{};", day);
+    if lines[first..=last].contains(&declaration.as_str()) {
+        return Err(invalid(format!("day{} is already declared", day)));
+    }
+
+    let mut block = lines[first..=last].to_vec();
+    block.push(&declaration);
+    block.sort_unstable_by_key(|line| line.trim_end_matches(';'));
+    lines.splice(first..=last, block);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The day number of a `day!(crate::dayN::DayN, ...)` line in the registry.
+fn registered_day(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix("day!(crate::day")?;
+    let (number, _) = rest.split_once("::")?;
+
+    number.parse().ok()
+}
+
+/// `registry` with a `day!` entry for the day added to `DAYS` in day order.
+pub fn register_day(registry: &str, day: u8) -> Result<String> {
+    let mut lines = registry.lines().collect::<Vec<&str>>();
+
+    let start = lines
+        .iter()
+        .position(|&line| line == DAYS_START)
+        .ok_or_else(|| invalid(format!("expected '{}'", DAYS_START)))?;
+    let end = lines[start..]
+        .iter()
+        .position(|&line| line == DAYS_END)
+        .map(|end| start + end)
+        .ok_or_else(|| invalid(format!("expected '{}' after DAYS", DAYS_END)))?;
+
+    let mut at = end;
+    for (i, &line) in lines.iter().enumerate().take(end).skip(start + 1) {
+        match registered_day(line) {
+            Some(number) if number == day => {
+                return Err(invalid(format!("day {} is already registered", day)))
+            }
+            Some(number) if number > day => {
+                at = i;
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let entry = format!("    day!(crate::day{0}::Day{0}),", day);
+    lines.insert(at, &entry);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Creates new days in the crate at `root`.
+pub struct Scaffold {
+    root: PathBuf,
+}
+
+impl Scaffold {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Scaffold { root: root.into() }
+    }
+
+    /// The scaffold for this crate.
+    pub fn local() -> Self {
+        Scaffold::new(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    pub fn module_path(&self, day: u8) -> PathBuf {
+        self.root.join("src").join(format!("day{}.rs", day))
+    }
+
+    fn edit(path: &Path, edit: impl FnOnce(&str) -> Result<String>) -> Result<String> {
+        let text = fs::read_to_string(path)?;
+
+        edit(&text).map_err(|err| match err {
+            Error::Io(err) => invalid(format!("{}: {}", path.display(), err)),
+            err => err,
+        })
+    }
+
+    /// Writes the module for `day`, declares and registers it, and adds an empty
+    /// example fixture unless there is one. Returns the files it changed.
+    /// Nothing is written if any step would fail.
+    pub fn create(&self, day: u8) -> Result<Vec<PathBuf>> {
+        if !(1..=LAST_DAY).contains(&day) {
+            return Err(invalid(format!("expected a day from 1 to {}", LAST_DAY)));
+        }
+
+        let module = self.module_path(day);
+        if module.exists() {
+            let reason = format!("{} already exists", module.display());
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                reason,
+            )));
+        }
+
+        let lib = self.root.join("src").join("lib.rs");
+        let registry = self.root.join("src").join("registry.rs");
+        let lib_text = Scaffold::edit(&lib, |text| declare_module(text, day))?;
+        let registry_text = Scaffold::edit(&registry, |text| register_day(text, day))?;
+
+        fs::write(&module, module_source(day))?;
+        fs::write(&lib, lib_text)?;
+        fs::write(&registry, registry_text)?;
+        let mut changed = vec![module, lib, registry];
+
+        let store = Store::new(self.root.join("input"));
+        let example = Fixture::named(day, "example");
+        if !store.path(&example).exists() {
+            store.add(&example, "", &[])?;
+            changed.push(store.path(&example));
+        }
+
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::position,
+        registry,
+        solution::{self, Answer},
+    };
+
+    const LIB: &str = include_str!("lib.rs");
+    const REGISTRY: &str = include_str!("registry.rs");
+
+    /// The first day the crate doesn't have yet.
+    fn new_day() -> u8 {
+        (1..=LAST_DAY)
+            .find(|&day| registry::find(day).is_none())
+            .unwrap()
+    }
+
+    #[test]
+    fn declare_module_test() {
+        let day = new_day();
+        let lib = declare_module(LIB, day).unwrap();
+
+        assert!(lib.contains(&format!("\npub mod day{};\n", day)));
+        assert_eq!(lib.lines().count(), LIB.lines().count() + 1);
+        assert!(declare_module(&lib, day).is_err());
+
+        assert_eq!(
+            declare_module("//! Days\n\npub mod day2;\npub mod day3;\n", 1).unwrap(),
+            "//! Days\n\npub mod day1;\npub mod day2;\npub mod day3;\n"
+        );
+        assert_eq!(
+            declare_module("pub mod day1;\npub mod day3;\n", 17).unwrap(),
+            "pub mod day1;\npub mod day17;\npub mod day3;\n"
+        );
+        assert!(declare_module("pub mod a;\n\npub mod b;\n", 1).is_err());
+        assert!(declare_module("//! No modules\n", 1).is_err());
+    }
+
+    #[test]
+    fn register_day_test() {
+        let day = new_day();
+        let registry = register_day(REGISTRY, day).unwrap();
+        assert!(registry.contains(&format!("\n    day!(crate::day{0}::Day{0}),\n", day)));
+        assert!(register_day(&registry, day).is_err());
+
+        let registry = "pub const DAYS: &[Day] = &[\n    day!(crate::day1::Day1),\n    \
+            day!(crate::day3::Day3, streaming),\n];\n";
+        assert_eq!(
+            register_day(registry, 2).unwrap(),
+            "pub const DAYS: &[Day] = &[\n    day!(crate::day1::Day1),\n    \
+             day!(crate::day2::Day2),\n    day!(crate::day3::Day3, streaming),\n];\n"
+        );
+        assert!(register_day(registry, 3).is_err());
+        assert!(register_day("const OTHER: u8 = 1;\n", 2).is_err());
+    }
+
+    #[test]
+    fn module_source_test() {
+        let day = new_day();
+        let module = module_source(day);
+
+        assert!(module.contains(&format!(
+            "impl Solution for Day{0} {{\n    const DAY: u8 = {0};",
+            day
+        )));
+        assert!(!module.contains("Day0") && !module.contains("day0"));
+    }
+
+    #[test]
+    fn template_checks() {
+        // The checks the suite runs on every registered day, on the day `aoc new` writes
+        type Day = template::Day0;
+
+        for input in ["", "forwrad 5 ~\n", "1,2 -> 3,4\n#.#\n"] {
+            assert!(solution::check_input::<Day>(input).is_ok(), "{:?}", input);
+        }
+
+        let input = "a\n  \nb\n";
+        let err = solution::check_input::<Day>(input).unwrap_err();
+        assert_eq!(position(&err), (2, 1, "  "));
+        let _ = err.excerpt(input);
+
+        let solved = solution::solve_timed_jobs::<Day>("a\nb\n", None, 2).unwrap();
+        assert_eq!(
+            solved.answers,
+            [(1, Answer::Number(0)), (2, Answer::Number(0))]
+        );
+    }
+
+    #[test]
+    fn create_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/registry.rs"), REGISTRY).unwrap();
+
+        let scaffold = Scaffold::new(&root);
+        let changed = scaffold.create(20).unwrap();
+
+        assert_eq!(changed.len(), 4);
+        let module = fs::read_to_string(scaffold.module_path(20)).unwrap();
+        assert!(module.contains("impl Solution for Day20 {\n    const DAY: u8 = 20;"));
+        assert!(module.contains("input/2021/day20/example.txt"));
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod day20;"));
+        assert_eq!(
+            fs::read_to_string(root.join("input/2021/day20/example.txt")).unwrap(),
+            ""
+        );
+
+        assert!(scaffold.create(20).is_err());
+        assert!(scaffold.create(26).is_err());
+
+        // Nothing is written when the registry can't be edited
+        fs::write(root.join("src/registry.rs"), "").unwrap();
+        assert!(scaffold.create(21).is_err());
+        assert!(!scaffold.module_path(21).exists());
+        assert!(!fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod day21;"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
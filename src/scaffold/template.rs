@@ -0,0 +1,47 @@
+use crate::{
+    error::{Error, Result},
+    solution::{Answer, Solution},
+};
+
+pub struct Day0;
+
+impl Solution for Day0 {
+    const DAY: u8 = 0;
+
+    type Input = Vec<String>;
+
+    // A line per item until the puzzle says otherwise
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .map(|line| match line.trim() {
+                "" => Err(Error::parse(input, line, "expected a line of input")),
+                line => Ok(line.to_string()),
+            })
+            .collect()
+    }
+
+    fn part1(_input: &Self::Input) -> Result<Answer> {
+        Ok(Answer::Number(0))
+    }
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        Ok(Answer::Number(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Paste the example here, and into input/2021/day0/example.txt for `aoc`
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn example() {
+        let input = Day0::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day0::part1(&input).unwrap(), Answer::Number(0));
+        assert_eq!(Day0::part2(&input).unwrap(), Answer::Number(0));
+    }
+}
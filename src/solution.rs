@@ -0,0 +1,322 @@
+use std::{
+    fmt,
+    io::BufRead,
+    time::{Duration, Instant},
+};
+
+use crate::{error::Result, parallel};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    Grid(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Grid(grid) => write!(f, "{}", grid),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(i32, u32, i64, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// The answers to the parts that were solved, tagged with their part number.
+pub type Answers = Vec<(u8, Answer)>;
+
+/// A day's puzzle. The input text is parsed once and both parts are solved from
+/// the parsed form, possibly at the same time on different threads.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input: Sync;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+/// A [`Solution`] that can also read its input a line at a time, keeping only a
+/// summary of the lines so far, so inputs larger than memory still work.
+pub trait Streaming: Solution {
+    type Summary: Default + Sync;
+
+    /// Adds one line, without its line ending, to the summary. Parse errors are
+    /// positioned within `line`.
+    fn add_line(summary: &mut Self::Summary, line: &str) -> Result<()>;
+    fn summary_part1(summary: &Self::Summary) -> Result<Answer>;
+    fn summary_part2(summary: &Self::Summary) -> Result<Answer>;
+}
+
+/// How long parsing and each solved part took.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub parts: Vec<(u8, Duration)>,
+}
+
+impl Timings {
+    pub fn part(&self, number: u8) -> Option<Duration> {
+        self.parts
+            .iter()
+            .find(|&&(part, _)| part == number)
+            .map(|&(_, time)| time)
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse + self.parts.iter().map(|&(_, time)| time).sum::<Duration>()
+    }
+}
+
+/// The answers for a day along with how long they took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solved {
+    pub answers: Answers,
+    pub timings: Timings,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+
+    (result, start.elapsed())
+}
+
+/// Parses `input` without solving it, tagging errors with the solution's day.
+pub fn check_input<S: Solution>(input: &str) -> Result<()> {
+    S::parse(input)
+        .map(|_| ())
+        .map_err(|err| err.in_day(S::DAY))
+}
+
+/// Solves the selected parts with `solve_part`, both at once if `jobs` allows,
+/// adding them to `solved` in part order.
+fn solve_parts(
+    solved: &mut Solved,
+    part: Option<u8>,
+    day: u8,
+    jobs: usize,
+    solve_part: impl Fn(u8) -> Result<Answer> + Sync,
+) -> Result<()> {
+    let numbers = [1, 2]
+        .into_iter()
+        .filter(|&number| part.is_none_or(|part| part == number))
+        .collect::<Vec<u8>>();
+
+    let results = parallel::map(&numbers, jobs, |&number| timed(|| solve_part(number)));
+    for (number, (answer, time)) in numbers.into_iter().zip(results) {
+        solved
+            .answers
+            .push((number, answer.map_err(|err| err.in_day(day))?));
+        solved.timings.parts.push((number, time));
+    }
+
+    Ok(())
+}
+
+/// Parses `input` and solves the selected part, or both parts when `part` is `None`,
+/// timing each step. Errors are tagged with the solution's day.
+pub fn solve_timed<S: Solution>(input: &str, part: Option<u8>) -> Result<Solved> {
+    solve_timed_jobs::<S>(input, part, 1)
+}
+
+/// Like [`solve_timed`], but solves both parts at the same time when `jobs` is
+/// more than 1. Each part's time is still its own.
+pub fn solve_timed_jobs<S: Solution>(input: &str, part: Option<u8>, jobs: usize) -> Result<Solved> {
+    let (parsed, parse_time) = timed(|| S::parse(input));
+    let parsed = parsed.map_err(|err| err.in_day(S::DAY))?;
+
+    let mut solved = Solved {
+        answers: Vec::new(),
+        timings: Timings {
+            parse: parse_time,
+            parts: Vec::new(),
+        },
+    };
+    solve_parts(&mut solved, part, S::DAY, jobs, |number| match number {
+        1 => S::part1(&parsed),
+        _ => S::part2(&parsed),
+    })?;
+
+    Ok(solved)
+}
+
+fn add_lines<S: Streaming>(reader: &mut dyn BufRead) -> Result<S::Summary> {
+    let mut summary = S::Summary::default();
+    let mut line = String::new();
+    let mut number = 0;
+
+    while reader.read_line(&mut line)? > 0 {
+        number += 1;
+
+        let text = line.strip_suffix('\n').unwrap_or(&line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        S::add_line(&mut summary, text).map_err(|err| err.on_line(number))?;
+
+        line.clear();
+    }
+
+    Ok(summary)
+}
+
+/// Like [`solve_timed`], but reads the input from `reader` a line at a time. The
+/// parse time covers reading and summarising the whole input.
+pub fn solve_stream<S: Streaming>(reader: &mut dyn BufRead, part: Option<u8>) -> Result<Solved> {
+    let (summary, parse_time) = timed(|| add_lines::<S>(reader));
+    let summary = summary.map_err(|err| err.in_day(S::DAY))?;
+
+    let mut solved = Solved {
+        answers: Vec::new(),
+        timings: Timings {
+            parse: parse_time,
+            parts: Vec::new(),
+        },
+    };
+    // Answering from a summary is quick, so the parts take turns
+    solve_parts(&mut solved, part, S::DAY, 1, |number| match number {
+        1 => S::summary_part1(&summary),
+        _ => S::summary_part2(&summary),
+    })?;
+
+    Ok(solved)
+}
+
+/// Parses `input` and solves the selected part, or both parts when `part` is `None`.
+/// Errors are tagged with the solution's day.
+pub fn solve<S: Solution>(input: &str, part: Option<u8>) -> Result<Answers> {
+    solve_timed::<S>(input, part).map(|solved| solved.answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+
+        type Input = Vec<i32>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            input
+                .split(',')
+                .map(|s| {
+                    s.parse()
+                        .map_err(|_| Error::parse(input, s, "expected a number"))
+                })
+                .collect()
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            Ok(input.iter().sum::<i32>().into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            if input.len() > 3 {
+                return Err(Error::unsolvable("too many numbers"));
+            }
+
+            Ok(format!("{} numbers", input.len()).into())
+        }
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(
+            Answer::from(1601616884019_usize).to_string(),
+            "1601616884019"
+        );
+        assert_eq!(Answer::from(-3).to_string(), "-3");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+        assert_eq!(Answer::Grid("#.\n.#".to_string()).to_string(), "#.\n.#");
+    }
+
+    #[test]
+    fn solve_test() {
+        assert_eq!(
+            solve::<Sum>("1,2,3", None).unwrap(),
+            vec![(1, Answer::Number(6)), (2, Answer::from("3 numbers"))]
+        );
+        assert_eq!(
+            solve::<Sum>("1,2,3", Some(1)).unwrap(),
+            vec![(1, Answer::Number(6))]
+        );
+        assert_eq!(
+            solve::<Sum>("1,2,3", Some(2)).unwrap(),
+            vec![(2, Answer::from("3 numbers"))]
+        );
+    }
+
+    #[test]
+    fn solve_timed_test() {
+        let solved = solve_timed::<Sum>("1,2,3", Some(2)).unwrap();
+
+        assert_eq!(solved.answers, vec![(2, Answer::from("3 numbers"))]);
+        assert_eq!(solved.timings.parts.len(), 1);
+        assert!(solved.timings.part(1).is_none());
+        assert!(solved.timings.total() >= solved.timings.part(2).unwrap());
+    }
+
+    #[test]
+    fn solve_timed_jobs_test() {
+        let solved = solve_timed_jobs::<Sum>("1,2,3", None, 2).unwrap();
+
+        assert_eq!(solved.answers, solve::<Sum>("1,2,3", None).unwrap());
+        assert_eq!(
+            solved
+                .timings
+                .parts
+                .iter()
+                .map(|&(part, _)| part)
+                .collect::<Vec<u8>>(),
+            vec![1, 2]
+        );
+        assert!(matches!(
+            solve_timed_jobs::<Sum>("1,2,3,4", None, 2),
+            Err(Error::Unsolvable { day: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn solve_errors() {
+        assert!(matches!(
+            solve::<Sum>("1,x,3", None),
+            Err(Error::Parse { column: 3, .. })
+        ));
+        assert!(solve::<Sum>("1,2,3,4", Some(1)).is_ok());
+        assert!(matches!(
+            solve::<Sum>("1,2,3,4", Some(2)),
+            Err(Error::Unsolvable { .. })
+        ));
+    }
+}
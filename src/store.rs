@@ -0,0 +1,321 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    answers::Answers,
+    error::{Error, Result},
+    registry,
+    solution::Answer,
+};
+
+/// The year the solutions in this crate are for.
+pub const YEAR: u16 = 2021;
+
+/// The fixture name of a day's real puzzle input.
+pub const REAL_INPUT: &str = "input";
+
+const ANSWERS_FILE: &str = "answers.toml";
+
+/// A named input for a year and day, such as the real input or an example.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fixture {
+    pub year: u16,
+    pub day: u8,
+    pub name: String,
+}
+
+impl Fixture {
+    pub fn new(year: u16, day: u8, name: impl Into<String>) -> Self {
+        Fixture {
+            year,
+            day,
+            name: name.into(),
+        }
+    }
+
+    /// A fixture for this crate's year.
+    pub fn named(day: u8, name: impl Into<String>) -> Self {
+        Fixture::new(YEAR, day, name)
+    }
+
+    /// The real puzzle input for `day` of this crate's year.
+    pub fn real(day: u8) -> Self {
+        Fixture::named(day, REAL_INPUT)
+    }
+}
+
+impl fmt::Display for Fixture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/day{}/{}", self.year, self.day, self.name)
+    }
+}
+
+/// The answer to one part of a fixture, and the answer it should have had if
+/// one is known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartCheck {
+    pub part: u8,
+    pub expected: Option<Answer>,
+    pub actual: Answer,
+}
+
+impl PartCheck {
+    /// True unless a known answer differs.
+    pub fn passed(&self) -> bool {
+        self.expected
+            .as_ref()
+            .is_none_or(|expected| *expected == self.actual)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validation {
+    pub fixture: Fixture,
+    pub parts: Vec<PartCheck>,
+}
+
+impl Validation {
+    pub fn passed(&self) -> bool {
+        self.parts.iter().all(PartCheck::passed)
+    }
+}
+
+fn day_number(dir_name: &str) -> Option<u8> {
+    dir_name.strip_prefix("day")?.parse().ok()
+}
+
+fn check_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::parse(
+            name,
+            name,
+            "expected a fixture name of letters, digits, - and _",
+        ))
+    }
+}
+
+/// Sorted entries of `dir`, or none if it doesn't exist.
+fn entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<PathBuf>>>()?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    paths.sort();
+
+    Ok(paths)
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("")
+}
+
+/// Fixtures kept on disk as `<root>/<year>/day<N>/<name>.txt`, with the expected
+/// answers for each of a day's fixtures in `<root>/<year>/day<N>/answers.toml`.
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Store { root: root.into() }
+    }
+
+    /// The store in this crate's `input` directory.
+    pub fn local() -> Self {
+        Store::new(concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn day_dir(&self, year: u16, day: u8) -> PathBuf {
+        self.root.join(year.to_string()).join(format!("day{}", day))
+    }
+
+    pub fn path(&self, fixture: &Fixture) -> PathBuf {
+        self.day_dir(fixture.year, fixture.day)
+            .join(format!("{}.txt", fixture.name))
+    }
+
+    pub fn read(&self, fixture: &Fixture) -> Result<String> {
+        Ok(fs::read_to_string(self.path(fixture))?)
+    }
+
+    /// The expected answers for a day's fixtures, empty if none are recorded.
+    pub fn answers(&self, year: u16, day: u8) -> Result<Answers> {
+        match fs::read_to_string(self.day_dir(year, day).join(ANSWERS_FILE)) {
+            Ok(text) => Answers::parse(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// The known answer to each part of `fixture`.
+    pub fn expected(&self, fixture: &Fixture) -> Result<Vec<(u8, Answer)>> {
+        let answers = self.answers(fixture.year, fixture.day)?;
+
+        Ok(answers
+            .iter()
+            .filter(|&(name, _, _)| name == fixture.name)
+            .map(|(_, part, answer)| (part, answer.clone()))
+            .collect())
+    }
+
+    /// The fixtures of one day, by name.
+    pub fn list_day(&self, year: u16, day: u8) -> Result<Vec<Fixture>> {
+        Ok(entries(&self.day_dir(year, day))?
+            .iter()
+            .filter_map(|path| file_name(path).strip_suffix(".txt"))
+            .map(|name| Fixture::new(year, day, name))
+            .collect())
+    }
+
+    /// Every fixture in the store, by year, day and name.
+    pub fn list(&self) -> Result<Vec<Fixture>> {
+        let mut fixtures = Vec::new();
+
+        for year_dir in entries(&self.root)? {
+            let year = match file_name(&year_dir).parse::<u16>() {
+                Ok(year) => year,
+                Err(_) => continue,
+            };
+
+            for day_dir in entries(&year_dir)? {
+                if let Some(day) = day_number(file_name(&day_dir)) {
+                    fixtures.extend(self.list_day(year, day)?);
+                }
+            }
+        }
+
+        fixtures.sort();
+        Ok(fixtures)
+    }
+
+    /// Saves `input` as `fixture` along with any known answers, replacing what
+    /// was stored under that name.
+    pub fn add(&self, fixture: &Fixture, input: &str, expected: &[(u8, Answer)]) -> Result<()> {
+        check_name(&fixture.name)?;
+
+        let mut answers = self.answers(fixture.year, fixture.day)?;
+        for (part, answer) in expected {
+            answers.insert(&fixture.name, *part, answer.clone());
+        }
+
+        fs::create_dir_all(self.day_dir(fixture.year, fixture.day))?;
+        fs::write(self.path(fixture), input)?;
+        if !expected.is_empty() {
+            let path = self.day_dir(fixture.year, fixture.day).join(ANSWERS_FILE);
+            fs::write(path, answers.to_toml())?;
+        }
+
+        Ok(())
+    }
+
+    /// Solves `fixture` and compares the answers with the recorded ones.
+    pub fn validate(&self, fixture: &Fixture) -> Result<Validation> {
+        let day = registry::find(fixture.day)
+            .filter(|_| fixture.year == YEAR)
+            .ok_or_else(|| Error::unsolvable(format!("no solution for {}", fixture)))?;
+
+        let expected = self.expected(fixture)?;
+        let parts = day
+            .solve(&self.read(fixture)?, None)?
+            .into_iter()
+            .map(|(part, actual)| PartCheck {
+                part,
+                expected: expected
+                    .iter()
+                    .find(|&&(number, _)| number == part)
+                    .map(|(_, answer)| answer.clone()),
+                actual,
+            })
+            .collect();
+
+        Ok(Validation {
+            fixture: fixture.clone(),
+            parts,
+        })
+    }
+}
+
+/// Reads a fixture of this crate's year from the local store, for tests.
+///
+/// Panics if it is missing.
+pub fn fixture(day: u8, name: &str) -> String {
+    let fixture = Fixture::named(day, name);
+
+    Store::local()
+        .read(&fixture)
+        .unwrap_or_else(|err| panic!("fixture {}: {}", fixture, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> Store {
+        let root = std::env::temp_dir().join(format!("aoc-store-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        Store::new(root)
+    }
+
+    #[test]
+    fn local_fixtures() {
+        let store = Store::local();
+        let day1 = store.list_day(YEAR, 1).unwrap();
+
+        assert!(day1.contains(&Fixture::named(1, "example")));
+        assert_eq!(
+            store.expected(&Fixture::named(1, "example")).unwrap(),
+            vec![(1, Answer::Number(7)), (2, Answer::Number(5))]
+        );
+        assert!(fixture(1, "example").starts_with("199\n200\n"));
+    }
+
+    #[test]
+    fn add_list_validate() {
+        let store = temp_store("add");
+        let fixture = Fixture::named(1, "short");
+
+        store
+            .add(&fixture, "1\n2\n3\n4\n", &[(1, Answer::Number(3))])
+            .unwrap();
+        store
+            .add(&Fixture::named(6, "one"), "3", &[(2, Answer::Number(7))])
+            .unwrap();
+
+        assert_eq!(
+            store.list().unwrap(),
+            vec![fixture.clone(), Fixture::named(6, "one")]
+        );
+
+        let validation = store.validate(&fixture).unwrap();
+        assert!(validation.passed());
+        assert_eq!(validation.parts[0].expected, Some(Answer::Number(3)));
+        assert_eq!(validation.parts[1].expected, None);
+
+        let validation = store.validate(&Fixture::named(6, "one")).unwrap();
+        assert!(!validation.passed());
+
+        assert!(store.add(&Fixture::named(1, "../up"), "1", &[]).is_err());
+        assert!(store.validate(&Fixture::new(2020, 1, "short")).is_err());
+
+        fs::remove_dir_all(store.root()).unwrap();
+    }
+}
@@ -0,0 +1,506 @@
+//! Submitting answers to the puzzle server, and a local log of every answer
+//! sent so that known-wrong answers are never sent twice.
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::error::{self, Error, Result};
+
+/// Where answers go unless another base URL is given.
+pub const DEFAULT_URL: &str = "https://adventofcode.com";
+
+/// The name of each day's answer log in the fixture store.
+pub const LOG_FILE: &str = "submissions.log";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Another answer was sent too recently; this one wasn't checked.
+    RateLimited,
+    /// The part was already solved, or isn't open yet; the answer wasn't checked.
+    WrongLevel,
+}
+
+impl Verdict {
+    const ALL: [Verdict; 6] = [
+        Verdict::Right,
+        Verdict::Wrong,
+        Verdict::TooHigh,
+        Verdict::TooLow,
+        Verdict::RateLimited,
+        Verdict::WrongLevel,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Verdict::Right => "right",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too high",
+            Verdict::TooLow => "too low",
+            Verdict::RateLimited => "rate limited",
+            Verdict::WrongLevel => "wrong level",
+        }
+    }
+
+    /// True if the server checked the answer and turned it down.
+    pub fn rejected(self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The verdict in a response page, if it has one.
+pub fn parse_verdict(page: &str) -> Option<Verdict> {
+    let verdict = if page.contains("That's the right answer") {
+        Verdict::Right
+    } else if page.contains("That's not the right answer") {
+        if page.contains("too high") {
+            Verdict::TooHigh
+        } else if page.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if page.contains("You gave an answer too recently") {
+        Verdict::RateLimited
+    } else if page.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        return None;
+    };
+
+    Some(verdict)
+}
+
+/// The text of the page's `<article>` (or the whole page), without tags and
+/// with runs of whitespace collapsed.
+pub fn page_text(page: &str) -> String {
+    let article = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// The server's reply to a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub verdict: Verdict,
+    pub message: String,
+}
+
+fn server_error(message: String) -> Error {
+    Error::Io(io::Error::other(message))
+}
+
+/// Posts answers to a puzzle server at `base_url` with a session cookie.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(TIMEOUT)
+                .user_agent(concat!("advent-of-code-2021/", env!("CARGO_PKG_VERSION")))
+                .build(),
+        }
+    }
+
+    pub fn answer_url(&self, year: u16, day: u8) -> String {
+        format!("{}/{}/day/{}/answer", self.base_url, year, day)
+    }
+
+    pub fn submit(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<Response> {
+        let url = self.answer_url(year, day);
+        let level = part.to_string();
+
+        let page = match self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &level), ("answer", answer)])
+        {
+            Ok(response) => response.into_string()?,
+            Err(ureq::Error::Status(status, _)) => {
+                return Err(server_error(format!("{} returned status {}", url, status)))
+            }
+            Err(err) => return Err(server_error(format!("could not reach {}: {}", url, err))),
+        };
+
+        let message = page_text(&page);
+        match parse_verdict(&page) {
+            Some(verdict) => Ok(Response { verdict, message }),
+            None => Err(server_error(format!(
+                "unrecognised response from {}: {}",
+                url, message
+            ))),
+        }
+    }
+}
+
+/// One answer sent for a part, and the server's verdict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.part, self.answer, self.verdict)
+    }
+}
+
+fn parse_entry(line: &str) -> Result<Entry> {
+    let mut fields = line.splitn(3, ' ');
+    let (part, answer, verdict) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(part), Some(answer), Some(verdict)) => (part, answer, verdict),
+        _ => {
+            return Err(Error::parse(
+                line,
+                line,
+                "expected a part, answer and verdict",
+            ))
+        }
+    };
+
+    Ok(Entry {
+        part: error::parse_token(line, part, "a part number")?,
+        answer: answer.to_string(),
+        verdict: Verdict::ALL
+            .into_iter()
+            .find(|known| known.name() == verdict)
+            .ok_or_else(|| Error::parse(line, verdict, "expected a verdict"))?,
+    })
+}
+
+/// Every answer sent for one day, one `part answer verdict` line each, kept in
+/// a file next to the day's fixtures.
+#[derive(Debug)]
+pub struct Log {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+/// What happened to an answer given to [`submit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    /// The log already shows the answer is wrong, or that the part is solved.
+    Refused(String),
+    Sent(Response),
+}
+
+impl Log {
+    /// Reads the log at `path`, which is empty if the file doesn't exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let entries = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| parse_entry(line).map_err(|err| err.on_line(i + 1)))
+            .collect::<Result<Vec<Entry>>>()?;
+
+        Ok(Log { path, entries })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Why `answer` shouldn't be sent for `part`, if the log already knows what
+    /// the server would say: it was sent before, it is past a too high or too low
+    /// bound, or the part is already solved.
+    pub fn refusal(&self, part: u8, answer: &str) -> Option<String> {
+        let sent = self.entries.iter().filter(|entry| entry.part == part);
+        let number = answer.parse::<i64>().ok();
+
+        for entry in sent {
+            let bound = entry.answer.parse::<i64>().ok();
+            let reason = match (entry.verdict, number, bound) {
+                (Verdict::Right, _, _) if entry.answer == answer => {
+                    format!("{} is already known to be right", answer)
+                }
+                (Verdict::Right, _, _) => {
+                    format!("part {} was already solved with {}", part, entry.answer)
+                }
+                (verdict, _, _) if verdict.rejected() && entry.answer == answer => {
+                    format!("{} was already {}", answer, verdict)
+                }
+                (Verdict::TooHigh, Some(number), Some(bound)) if number > bound => {
+                    format!("{} is above {}, which was too high", answer, bound)
+                }
+                (Verdict::TooLow, Some(number), Some(bound)) if number < bound => {
+                    format!("{} is below {}, which was too low", answer, bound)
+                }
+                _ => continue,
+            };
+
+            return Some(reason);
+        }
+
+        None
+    }
+
+    /// Adds an entry to the log and appends it to the file.
+    pub fn record(&mut self, part: u8, answer: &str, verdict: Verdict) -> Result<()> {
+        let entry = Entry {
+            part,
+            answer: answer.to_string(),
+            verdict,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", entry)?;
+
+        self.entries.push(entry);
+        Ok(())
+    }
+}
+
+/// Sends `answer` for a part unless the log says not to, and logs the verdict.
+pub fn submit(
+    client: &Client,
+    log: &mut Log,
+    (year, day, part): (u16, u8, u8),
+    answer: &str,
+) -> Result<Submission> {
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        return Err(Error::parse(
+            answer,
+            answer,
+            "expected an answer without spaces",
+        ));
+    }
+    if let Some(reason) = log.refusal(part, answer) {
+        return Ok(Submission::Refused(reason));
+    }
+
+    let response = client.submit(year, day, part, answer)?;
+    log.record(part, answer, response.verdict)?;
+
+    Ok(Submission::Sent(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    const RIGHT: &str = "<html><body><main><article><p>That's the right answer!  \
+        You are <em>one gold star</em> closer.</p></article></main></body></html>";
+    const TOO_HIGH: &str = "<main><article><p>That's not the right answer; \
+        your answer is too high.  Please wait one minute.</p></article></main>";
+    const TOO_LOW: &str = "<article><p>That's not the right answer; \
+        your answer is too low.</p></article>";
+    const WRONG: &str = "<article><p>That's not the right answer.  If you're stuck, \
+        make sure you're using the full input data.</p></article>";
+    const TOO_RECENT: &str = "<article><p>You gave an answer too recently; you have \
+        to wait after submitting an answer before trying again.  \
+        You have 37s left to wait.</p></article>";
+    const WRONG_LEVEL: &str = "<article><p>You don't seem to be solving the right \
+        level.  Did you already complete it?</p></article>";
+
+    /// A one-shot stand-in for the puzzle server: answers the first request with
+    /// `status` and `page`, and returns the request it got.
+    fn serve_once(status: &str, page: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let status = status.to_string();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut length = 0;
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                status,
+                page.len(),
+                page
+            )
+            .unwrap();
+
+            request
+        });
+
+        (url, server)
+    }
+
+    fn temp_log(name: &str) -> Log {
+        let path = std::env::temp_dir()
+            .join(format!("aoc-submit-{}-{}", name, std::process::id()))
+            .join(LOG_FILE);
+        let _ = fs::remove_file(&path);
+
+        Log::open(path).unwrap()
+    }
+
+    #[test]
+    fn parse_verdict_test() {
+        assert_eq!(parse_verdict(RIGHT), Some(Verdict::Right));
+        assert_eq!(parse_verdict(TOO_HIGH), Some(Verdict::TooHigh));
+        assert_eq!(parse_verdict(TOO_LOW), Some(Verdict::TooLow));
+        assert_eq!(parse_verdict(WRONG), Some(Verdict::Wrong));
+        assert_eq!(parse_verdict(TOO_RECENT), Some(Verdict::RateLimited));
+        assert_eq!(parse_verdict(WRONG_LEVEL), Some(Verdict::WrongLevel));
+        assert_eq!(parse_verdict("<html>Puzzle inputs differ</html>"), None);
+
+        assert_eq!(
+            page_text(RIGHT),
+            "That's the right answer! You are one gold star closer."
+        );
+    }
+
+    #[test]
+    fn submit_to_server() {
+        let (url, server) = serve_once("200 OK", TOO_HIGH);
+        let client = Client::new(&format!("{}/", url), "abc123");
+
+        let response = client.submit(2021, 1, 2, "1400").unwrap();
+        assert_eq!(response.verdict, Verdict::TooHigh);
+        assert!(response.message.starts_with("That's not the right answer"));
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2021/day/1/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=abc123\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=1400"));
+    }
+
+    #[test]
+    fn server_errors() {
+        let (url, server) = serve_once("404 Not Found", "<html>Not found</html>");
+        assert!(Client::new(&url, "abc").submit(2021, 30, 1, "1").is_err());
+        server.join().unwrap();
+
+        let (url, server) = serve_once("200 OK", "<html>Something else</html>");
+        assert!(Client::new(&url, "abc").submit(2021, 1, 1, "1").is_err());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn log_refusals() {
+        let mut log = temp_log("refusals");
+        log.record(1, "1500", Verdict::TooHigh).unwrap();
+        log.record(1, "1000", Verdict::TooLow).unwrap();
+        log.record(1, "1200", Verdict::Wrong).unwrap();
+        log.record(1, "1300", Verdict::RateLimited).unwrap();
+        log.record(2, "ABC", Verdict::Right).unwrap();
+
+        assert!(log.refusal(1, "1500").is_some());
+        assert!(log.refusal(1, "1600").is_some());
+        assert!(log.refusal(1, "999").is_some());
+        assert!(log.refusal(1, "1200").is_some());
+        assert_eq!(log.refusal(1, "1300"), None);
+        assert_eq!(log.refusal(1, "1393"), None);
+        assert_eq!(log.refusal(1, "XYZ"), None);
+        assert!(log.refusal(2, "ABC").is_some());
+        assert!(log.refusal(2, "ABD").is_some());
+
+        let reopened = Log::open(log.path()).unwrap();
+        assert_eq!(reopened.entries(), log.entries());
+
+        fs::write(log.path(), "1 1500 too high\n1 1600 maybe\n").unwrap();
+        assert!(Log::open(log.path()).is_err());
+
+        fs::remove_file(log.path()).unwrap();
+    }
+
+    #[test]
+    fn submit_once() {
+        let (url, server) = serve_once("200 OK", TOO_LOW);
+        let client = Client::new(&url, "abc");
+        let mut log = temp_log("once");
+
+        let sent = submit(&client, &mut log, (2021, 1, 1), "1000").unwrap();
+        assert!(matches!(
+            sent,
+            Submission::Sent(Response {
+                verdict: Verdict::TooLow,
+                ..
+            })
+        ));
+        server.join().unwrap();
+
+        // Nothing is listening any more, so this must not reach the server
+        let again = submit(&client, &mut log, (2021, 1, 1), "900").unwrap();
+        assert!(matches!(again, Submission::Refused(_)));
+        assert_eq!(Log::open(log.path()).unwrap().entries().len(), 1);
+
+        assert!(submit(&client, &mut log, (2021, 1, 1), "10 00").is_err());
+        fs::remove_file(log.path()).unwrap();
+    }
+}
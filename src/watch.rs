@@ -0,0 +1,593 @@
+//! Stepping through the simulation days one step at a time in the terminal.
+//! Each day's state is wrapped in a [`Simulation`], and [`watch`] shows it with
+//! its counters while the keyboard pauses, steps and changes the speed.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue, terminal,
+};
+
+use crate::{
+    day11::{Board, Day11},
+    day13::{self, Day13, Dots, Inst, Size},
+    day14::{self, Day14, RuleSet},
+    day6::{self, Day6},
+    error::Result,
+    solution::Solution,
+};
+
+/// A simulation that can be advanced and shown one step at a time.
+pub trait Simulation {
+    /// Advances one step, or returns false if the simulation is over.
+    fn step(&mut self) -> bool;
+
+    /// The current state, as lines of text.
+    fn view(&self) -> String;
+
+    /// Named numbers describing the current state.
+    fn counters(&self) -> Vec<(&'static str, String)>;
+}
+
+/// The flashing octopuses of day 11.
+pub struct Octopuses {
+    board: Board,
+    steps: usize,
+    flashes: usize,
+    last_flashes: usize,
+    first_sync: Option<usize>,
+}
+
+impl Octopuses {
+    pub fn new(board: Board) -> Self {
+        Octopuses {
+            board,
+            steps: 0,
+            flashes: 0,
+            last_flashes: 0,
+            first_sync: None,
+        }
+    }
+}
+
+impl Simulation for Octopuses {
+    fn step(&mut self) -> bool {
+        self.last_flashes = self.board.step();
+        self.flashes += self.last_flashes;
+        self.steps += 1;
+
+        if self.first_sync.is_none() && self.board.all_flashing() {
+            self.first_sync = Some(self.steps);
+        }
+
+        true
+    }
+
+    /// Energy levels, with the octopuses that just flashed as `*`.
+    fn view(&self) -> String {
+        let mut view = String::new();
+
+        for row in self.board.energy.rows() {
+            for &energy in row {
+                match energy {
+                    0 if self.steps > 0 => view.push('*'),
+                    energy => view.push_str(&energy.to_string()),
+                }
+            }
+            view.push('\n');
+        }
+
+        view
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("step", self.steps.to_string()),
+            ("flashes this step", self.last_flashes.to_string()),
+            ("total flashes", self.flashes.to_string()),
+            (
+                "first all flashed",
+                self.first_sync
+                    .map_or_else(|| "not yet".to_string(), |step| step.to_string()),
+            ),
+        ]
+    }
+}
+
+/// `count` as a bar of `#` scaled against `most`, at most `width` long.
+fn bar(count: usize, most: usize, width: usize) -> String {
+    let length = if most == 0 {
+        0
+    } else {
+        (count as u128 * width as u128).div_ceil(most as u128) as usize
+    };
+
+    "#".repeat(length)
+}
+
+/// The lanternfish of day 6, counted by timer.
+pub struct Lanternfish {
+    fish: [usize; 9],
+    days: usize,
+}
+
+impl Lanternfish {
+    pub fn new(fish: [usize; 9]) -> Self {
+        Lanternfish { fish, days: 0 }
+    }
+
+    pub fn total(&self) -> usize {
+        self.fish.iter().sum()
+    }
+}
+
+impl Simulation for Lanternfish {
+    /// Stops before the number of fish would overflow.
+    fn step(&mut self) -> bool {
+        if self.total().checked_add(self.fish[0]).is_none() {
+            return false;
+        }
+
+        day6::tick_fish(&mut self.fish);
+        self.days += 1;
+        true
+    }
+
+    /// A bar for the number of fish with each timer.
+    fn view(&self) -> String {
+        let most = self.fish.iter().copied().max().unwrap_or(0);
+        let mut view = String::new();
+
+        for (timer, &count) in self.fish.iter().enumerate() {
+            let _ = writeln!(view, "{} {:>16} {}", timer, count, bar(count, most, 50));
+        }
+
+        view
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("day", self.days.to_string()),
+            ("fish", self.total().to_string()),
+        ]
+    }
+}
+
+/// The polymer of day 14, as counts of its pairs and letters.
+pub struct Polymer {
+    pairs: HashMap<(char, char), usize>,
+    letters: HashMap<char, usize>,
+    rules: RuleSet,
+    steps: usize,
+}
+
+impl Polymer {
+    pub fn new(template: &str, rules: RuleSet) -> Self {
+        let mut letters = HashMap::new();
+        for c in template.chars() {
+            *letters.entry(c).or_insert(0) += 1;
+        }
+
+        Polymer {
+            pairs: day14::parse_initial(template),
+            letters,
+            rules,
+            steps: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The most common letter's count minus the least common one's.
+    pub fn spread(&self) -> usize {
+        let most = self.letters.values().max().unwrap_or(&0);
+        let least = self.letters.values().min().unwrap_or(&0);
+
+        most - least
+    }
+}
+
+impl Simulation for Polymer {
+    /// Stops before the length of the polymer, which at most doubles each
+    /// step, could overflow.
+    fn step(&mut self) -> bool {
+        if self.len() > usize::MAX / 2 {
+            return false;
+        }
+
+        self.pairs = day14::run_rules(&self.pairs, &self.rules, &mut self.letters);
+        self.steps += 1;
+        true
+    }
+
+    /// A bar for the count of each letter.
+    fn view(&self) -> String {
+        let letters = self.letters.iter().collect::<BTreeMap<_, _>>();
+        let most = letters.values().copied().max().copied().unwrap_or(0);
+        let mut view = String::new();
+
+        for (letter, &count) in letters {
+            let _ = writeln!(view, "{} {:>20} {}", letter, count, bar(count, most, 50));
+        }
+
+        view
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("step", self.steps.to_string()),
+            ("length", self.len().to_string()),
+            ("most - least common", self.spread().to_string()),
+        ]
+    }
+}
+
+/// The transparent paper of day 13, folded one instruction at a time.
+pub struct Paper {
+    dots: Dots,
+    size: Size,
+    folds: Vec<Inst>,
+    done: usize,
+}
+
+impl Paper {
+    pub fn new(dots: Dots, folds: Vec<Inst>) -> Self {
+        Paper {
+            size: day13::paper_size(&dots),
+            dots,
+            folds,
+            done: 0,
+        }
+    }
+}
+
+impl Simulation for Paper {
+    fn step(&mut self) -> bool {
+        match self.folds.get(self.done) {
+            Some(&fold) => {
+                self.dots = day13::fold_set(&self.dots, self.size, fold);
+                self.size = day13::fold_size(self.size, fold);
+                self.done += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn view(&self) -> String {
+        day13::render_board(&self.dots)
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let next = match self.folds.get(self.done) {
+            Some(Inst::X(x)) => format!("x={}", x),
+            Some(Inst::Y(y)) => format!("y={}", y),
+            None => "none".to_string(),
+        };
+
+        vec![
+            ("folds", format!("{} of {}", self.done, self.folds.len())),
+            ("next fold", next),
+            ("dots", self.dots.len().to_string()),
+        ]
+    }
+}
+
+fn parsed<S: Solution>(input: &str) -> Result<S::Input> {
+    S::parse(input).map_err(|err| err.in_day(S::DAY))
+}
+
+/// The simulation for `day`'s input, or `None` if that day has none.
+pub fn simulation(day: u8, input: &str) -> Option<Result<Box<dyn Simulation>>> {
+    let simulation = match day {
+        6 => parsed::<Day6>(input).map(|fish| Box::new(Lanternfish::new(fish)) as Box<_>),
+        11 => parsed::<Day11>(input).map(|board| Box::new(Octopuses::new(board)) as Box<_>),
+        13 => {
+            parsed::<Day13>(input).map(|(dots, folds)| Box::new(Paper::new(dots, folds)) as Box<_>)
+        }
+        14 => parsed::<Day14>(input)
+            .map(|(template, rules)| Box::new(Polymer::new(&template, rules)) as Box<_>),
+        _ => return None,
+    };
+
+    Some(simulation)
+}
+
+/// What a key press asks the player to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Pause, or carry on if paused.
+    Pause,
+    /// Advance one step and pause.
+    Step,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Control {
+    pub fn from_key(key: KeyCode) -> Option<Self> {
+        match key {
+            KeyCode::Char(' ') | KeyCode::Char('p') => Some(Control::Pause),
+            KeyCode::Char('s') | KeyCode::Char('.') | KeyCode::Right => Some(Control::Step),
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => Some(Control::Faster),
+            KeyCode::Char('-') | KeyCode::Down => Some(Control::Slower),
+            KeyCode::Char('q') | KeyCode::Esc => Some(Control::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// The fastest speed, in steps per second.
+pub const MAX_SPEED: u32 = 1024;
+
+/// Runs a simulation at a speed, keeping track of pauses and the end.
+pub struct Player {
+    pub simulation: Box<dyn Simulation>,
+    pub paused: bool,
+    /// Steps per second while playing.
+    pub speed: u32,
+    pub finished: bool,
+}
+
+impl Player {
+    /// A player paused at the start of `simulation`.
+    pub fn new(simulation: Box<dyn Simulation>) -> Self {
+        Player {
+            simulation,
+            paused: true,
+            speed: 4,
+            finished: false,
+        }
+    }
+
+    pub fn step(&mut self) {
+        if !self.finished {
+            self.finished = !self.simulation.step();
+        }
+    }
+
+    /// Acts on a control, returning false to quit.
+    pub fn control(&mut self, control: Control) -> bool {
+        match control {
+            Control::Pause => self.paused = !self.paused,
+            Control::Step => {
+                self.paused = true;
+                self.step();
+            }
+            Control::Faster => self.speed = (self.speed * 2).min(MAX_SPEED),
+            Control::Slower => self.speed = (self.speed / 2).max(1),
+            Control::Quit => return false,
+        }
+
+        true
+    }
+
+    /// How long to wait between steps while playing.
+    pub fn delay(&self) -> Duration {
+        Duration::from_secs(1) / self.speed
+    }
+
+    fn status(&self) -> String {
+        let state = if self.finished {
+            "finished"
+        } else if self.paused {
+            "paused"
+        } else {
+            "playing"
+        };
+
+        format!(
+            "{} at {} steps/s - space: pause, s: step, +/-: speed, q: quit",
+            state, self.speed
+        )
+    }
+
+    /// The whole screen: counters, controls and as much of the state as fits.
+    pub fn screen(&self, (width, height): (usize, usize)) -> Vec<String> {
+        let mut lines = self
+            .simulation
+            .counters()
+            .into_iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<String>>();
+        lines.push(self.status());
+        lines.push(String::new());
+
+        let view = self.simulation.view();
+        let rows = height.saturating_sub(lines.len());
+        lines.extend(view.lines().take(rows).map(String::from));
+
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect()
+    }
+}
+
+/// Leaves the terminal as it was, even if watching fails part way.
+struct RawScreen;
+
+impl RawScreen {
+    fn enter(out: &mut impl Write) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        Ok(RawScreen)
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn draw(out: &mut impl Write, player: &Player) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+
+    queue!(
+        out,
+        cursor::MoveTo(0, 0),
+        terminal::Clear(terminal::ClearType::All)
+    )?;
+    for line in player.screen((width as usize, height as usize)) {
+        write!(out, "{}\r\n", line)?;
+    }
+
+    out.flush()
+}
+
+/// Shows `simulation` in the terminal until the user quits.
+pub fn watch(simulation: Box<dyn Simulation>) -> io::Result<()> {
+    let mut out = io::stdout();
+    let _screen = RawScreen::enter(&mut out)?;
+    let mut player = Player::new(simulation);
+    let mut next_step = Instant::now();
+    let mut changed = true;
+
+    loop {
+        if changed {
+            draw(&mut out, &player)?;
+            changed = false;
+        }
+
+        let playing = !player.paused && !player.finished;
+        let wait = if playing {
+            next_step.saturating_duration_since(Instant::now())
+        } else {
+            Duration::from_millis(250)
+        };
+
+        if event::poll(wait)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if let Some(control) = Control::from_key(key.code) {
+                        if !player.control(control) {
+                            return Ok(());
+                        }
+                        next_step = Instant::now() + player.delay();
+                        changed = true;
+                    }
+                }
+                Event::Resize(_, _) => changed = true,
+                _ => (),
+            }
+        } else if playing {
+            player.step();
+            next_step = Instant::now() + player.delay();
+            changed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::fixture;
+
+    fn example(day: u8) -> Box<dyn Simulation> {
+        simulation(day, &fixture(day, "example")).unwrap().unwrap()
+    }
+
+    fn counter(simulation: &dyn Simulation, name: &str) -> String {
+        simulation
+            .counters()
+            .into_iter()
+            .find(|&(counter, _)| counter == name)
+            .map(|(_, value)| value)
+            .unwrap()
+    }
+
+    #[test]
+    fn steps_match_answers() {
+        let mut fish = example(6);
+        (0..80).for_each(|_| assert!(fish.step()));
+        assert_eq!(counter(&*fish, "fish"), "5934");
+
+        let mut octopuses = example(11);
+        (0..100).for_each(|_| assert!(octopuses.step()));
+        assert_eq!(counter(&*octopuses, "total flashes"), "1656");
+        assert_eq!(counter(&*octopuses, "first all flashed"), "not yet");
+        (100..195).for_each(|_| assert!(octopuses.step()));
+        assert_eq!(counter(&*octopuses, "first all flashed"), "195");
+        assert!(octopuses.view().starts_with("**********\n"));
+
+        let mut polymer = example(14);
+        (0..10).for_each(|_| assert!(polymer.step()));
+        assert_eq!(counter(&*polymer, "most - least common"), "1588");
+        assert_eq!(counter(&*polymer, "length"), "3073");
+
+        let mut paper = example(13);
+        assert_eq!(counter(&*paper, "next fold"), "y=7");
+        assert!(paper.step());
+        assert_eq!(counter(&*paper, "dots"), "17");
+        assert!(paper.step());
+        assert!(!paper.step());
+        assert_eq!(counter(&*paper, "folds"), "2 of 2");
+        assert!(paper.view().starts_with("#####\n#...#\n"));
+
+        assert!(simulation(1, "199").is_none());
+        assert!(simulation(6, "3,9").unwrap().is_err());
+    }
+
+    #[test]
+    fn stops_before_overflow() {
+        let mut fish = Lanternfish::new([0, 0, 0, 0, 0, 0, 0, 0, usize::MAX / 2]);
+        let steps = (0..1000).take_while(|_| fish.step()).count();
+
+        assert!(steps < 1000);
+        assert!(fish.total() >= usize::MAX / 2);
+    }
+
+    #[test]
+    fn player_controls() {
+        let mut player = Player::new(example(13));
+        assert!(player.paused);
+
+        assert!(player.control(Control::Pause));
+        assert!(!player.paused);
+        assert!(player.control(Control::Step));
+        assert!(player.paused);
+        assert_eq!(counter(&*player.simulation, "folds"), "1 of 2");
+
+        player.control(Control::Step);
+        player.control(Control::Step);
+        assert!(player.finished);
+
+        (0..20).for_each(|_| _ = player.control(Control::Faster));
+        assert_eq!(player.speed, MAX_SPEED);
+        (0..20).for_each(|_| _ = player.control(Control::Slower));
+        assert_eq!(player.delay(), Duration::from_secs(1));
+
+        assert!(!player.control(Control::Quit));
+        assert_eq!(Control::from_key(KeyCode::Char(' ')), Some(Control::Pause));
+        assert_eq!(Control::from_key(KeyCode::Char('x')), None);
+    }
+
+    #[test]
+    fn screen_fits() {
+        let player = Player::new(example(11));
+        let screen = player.screen((6, 8));
+
+        assert_eq!(screen.len(), 8);
+        assert_eq!(screen[0], "step: ");
+        assert!(screen.iter().all(|line| line.chars().count() <= 6));
+        assert_eq!(screen[6], "548314");
+        assert_eq!(screen[7], "274585");
+    }
+}
@@ -0,0 +1,47 @@
+use advent_of_code_2021::store::Store;
+
+/// Every stored fixture, including the real inputs, must still give its recorded
+/// answers.
+#[test]
+fn fixtures_match_answers() {
+    let store = Store::local();
+    let mut failures = Vec::new();
+
+    for fixture in store.list().expect("fixture store lists") {
+        let validation = match store.validate(&fixture) {
+            Ok(validation) => validation,
+            Err(err) => {
+                failures.push(format!("{} failed: {}", fixture, err));
+                continue;
+            }
+        };
+
+        for check in validation.parts.iter().filter(|check| !check.passed()) {
+            if let Some(expected) = &check.expected {
+                failures.push(format!(
+                    "{} part {}: expected {}, got {}",
+                    fixture, check.part, expected, check.actual
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+/// The answers recorded for the real inputs cover both parts of every day.
+#[test]
+fn real_answers_recorded() {
+    let store = Store::local();
+
+    for fixture in store.list().unwrap() {
+        if fixture.name == "input" {
+            assert_eq!(
+                store.expected(&fixture).unwrap().len(),
+                2,
+                "{} is missing answers",
+                fixture
+            );
+        }
+    }
+}